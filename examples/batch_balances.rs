@@ -0,0 +1,38 @@
+use alloy::primitives::address;
+use alloy::providers::ProviderBuilder;
+use alloy_erc20_full::LazyToken;
+use dotenvy::dotenv;
+use std::env;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    dotenv().ok();
+
+    let eth_rpc = env::var("ETH_MAINNET_RPC").unwrap();
+    let provider = ProviderBuilder::new().connect_http(eth_rpc.parse().unwrap());
+
+    let dai = LazyToken::new(
+        address!("6B175474E89094C44Da98b954EedeAC495271d0F"), // DAI
+        provider,
+    );
+
+    let accounts = [
+        address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"), // vitalik.eth
+        address!("28C6c06298d514Db089934071355E5743bf21d60"), // Binance 14
+        address!("21a31Ee1afC51d94C2eFcCAa2092aD1028285549"), // Binance 15
+        address!("DFd5293D8e347dFe59E90eFd55b2956a1343963d"), // Binance 16
+        address!("F977814e90dA44bFA03b6295A0616a897441aceC"), // Binance 8
+        address!("5041ed759Dd4aFc3a72b8192C143F72f4724081A"), // OKX
+        address!("47ac0Fb4F2D84898e4D9E7b4DaB3C24507a6D503"), // Binance reserve
+        address!("60FaAe176336dAb62e284Fe19B885B095d29fB7F"), // Maker PSM
+        address!("BE0eB53F46cd790Cd13851d5EFf43D12404d33E8"), // Binance 7
+        address!("40ec5B33f54e0E8A33A975908C5BA1c14e5BbbDf"), // Polygon bridge
+    ];
+
+    // all ten balances are read in a single multicall request
+    let balances = dai.balances_of(&accounts).await.unwrap();
+
+    for (account, balance) in accounts.iter().zip(balances) {
+        println!("{account}: {}", dai.get_balance(balance).await.unwrap());
+    }
+}
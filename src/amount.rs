@@ -0,0 +1,220 @@
+use alloy::primitives::U256;
+use bigdecimal::{
+    num_bigint::{BigInt, Sign},
+    BigDecimal, RoundingMode,
+};
+
+use crate::Erc20Error;
+
+/// Formats `amount` base units as a fixed-point string with `decimals`
+/// fractional digits.
+pub(crate) fn format_units(amount: U256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = decimals as usize;
+
+    if decimals == 0 {
+        return digits;
+    }
+
+    let digits = format!("{digits:0>width$}", width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+
+    format!("{integer}.{fraction}")
+}
+
+/// Formats `amount` base units of a token with `decimals` decimals for
+/// display, rounded to `display_decimals` fractional digits with `mode`.
+///
+/// If `group` is set, the integer part is grouped by thousands with `,`,
+/// e.g. `"1,234.56"`.
+pub fn format_amount_rounded(
+    amount: U256,
+    decimals: u8,
+    display_decimals: usize,
+    mode: RoundingMode,
+    group: bool,
+) -> String {
+    let amount = BigDecimal::new(
+        BigInt::from_bytes_be(Sign::Plus, &amount.to_be_bytes::<{ U256::BYTES }>()),
+        decimals as i64,
+    );
+    let rounded = amount
+        .with_scale_round(display_decimals as i64, mode)
+        .to_plain_string();
+
+    if !group {
+        return rounded;
+    }
+
+    let (integer, fraction) = match rounded.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (rounded.as_str(), None),
+    };
+    let mut grouped = String::with_capacity(rounded.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    grouped
+}
+
+/// Parses a decimal string into base units of a token with `decimals`
+/// decimals.
+///
+/// Leading and trailing whitespace and thousands separators (`,`) in the
+/// integer part are ignored.
+pub(crate) fn parse_units(s: &str, decimals: u8) -> Result<U256, Erc20Error> {
+    let trimmed = s.trim();
+    let (integer, fraction) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    let integer = integer.replace(',', "");
+
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(&integer) || !is_digits(fraction) {
+        return Err(Erc20Error::InvalidAmount(s.to_string()));
+    }
+
+    if fraction.len() > decimals as usize {
+        return Err(Erc20Error::TooManyDecimals(s.to_string(), decimals));
+    }
+
+    let digits = format!("{integer}{fraction:0<width$}", width = decimals as usize);
+
+    U256::from_str_radix(&digits, 10).map_err(|_| Erc20Error::AmountOverflow(s.to_string()))
+}
+
+/// Converts a decimal `amount` into base units of a token with `decimals`
+/// decimals.
+///
+/// Amounts with more fractional digits than `decimals` are rejected, unless
+/// `truncate` is set, in which case the extra digits are dropped.
+pub(crate) fn decimal_to_units(
+    amount: &BigDecimal,
+    decimals: u8,
+    truncate: bool,
+) -> Result<U256, Erc20Error> {
+    if amount.sign() == Sign::Minus {
+        return Err(Erc20Error::InvalidAmount(amount.to_string()));
+    }
+
+    let scaled = amount.with_scale(decimals as i64);
+
+    if !truncate && scaled != *amount {
+        return Err(Erc20Error::TooManyDecimals(amount.to_string(), decimals));
+    }
+
+    let (units, _) = scaled.into_bigint_and_exponent();
+    let (_, bytes) = units.to_bytes_be();
+
+    U256::try_from_be_slice(&bytes).ok_or_else(|| Erc20Error::AmountOverflow(amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy::primitives::U256;
+    use bigdecimal::{BigDecimal, RoundingMode};
+
+    use crate::Erc20Error;
+
+    use super::{decimal_to_units, format_amount_rounded, format_units, parse_units};
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(U256::from(1_000_000), 6), "1.000000");
+        assert_eq!(format_units(U256::from(1), 6), "0.000001");
+        assert_eq!(format_units(U256::from(1_234), 0), "1234");
+        assert_eq!(format_units(U256::ZERO, 2), "0.00");
+    }
+
+    #[test]
+    fn test_format_amount_rounded() {
+        let format =
+            |amount: u64, mode, group| format_amount_rounded(U256::from(amount), 3, 2, mode, group);
+
+        // Banker's rounding rounds ties to the even digit
+        assert_eq!(format(1_125, RoundingMode::HalfEven, false), "1.12");
+        assert_eq!(format(1_135, RoundingMode::HalfEven, false), "1.14");
+        assert_eq!(format(1_125, RoundingMode::HalfUp, false), "1.13");
+        assert_eq!(format(1_129, RoundingMode::Down, false), "1.12");
+
+        assert_eq!(
+            format(1_234_567_891, RoundingMode::HalfEven, true),
+            "1,234,567.89"
+        );
+        assert_eq!(format(123_456, RoundingMode::HalfEven, true), "123.46");
+        assert_eq!(format(0, RoundingMode::HalfEven, true), "0.00");
+        assert_eq!(
+            format_amount_rounded(U256::from(1_234_500), 3, 0, RoundingMode::HalfEven, true),
+            "1,234"
+        );
+        assert_eq!(
+            format_amount_rounded(U256::from(1_234_500), 3, 0, RoundingMode::HalfUp, true),
+            "1,235"
+        );
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_units(" 1.5 ", 6).unwrap(), U256::from(1_500_000));
+        assert_eq!(parse_units("1,234", 2).unwrap(), U256::from(123_400));
+        assert_eq!(parse_units(".5", 1).unwrap(), U256::from(5));
+        assert_eq!(parse_units("7.", 0).unwrap(), U256::from(7));
+
+        assert!(matches!(
+            parse_units("1.0000001", 6),
+            Err(Erc20Error::TooManyDecimals(..))
+        ));
+        assert!(matches!(
+            parse_units("-1", 6),
+            Err(Erc20Error::InvalidAmount(_))
+        ));
+        assert!(matches!(
+            parse_units(".", 6),
+            Err(Erc20Error::InvalidAmount(_))
+        ));
+        assert!(matches!(
+            parse_units(&U256::MAX.to_string(), 1),
+            Err(Erc20Error::AmountOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_decimal_to_units() {
+        let decimal = |s| BigDecimal::from_str(s).unwrap();
+
+        assert_eq!(
+            decimal_to_units(&decimal("1.5"), 6, false).unwrap(),
+            U256::from(1_500_000)
+        );
+        assert_eq!(
+            decimal_to_units(&decimal("1.0000019"), 6, true).unwrap(),
+            U256::from(1_000_001)
+        );
+        assert_eq!(
+            decimal_to_units(&decimal("1.000000"), 0, false).unwrap(),
+            U256::from(1)
+        );
+
+        assert!(matches!(
+            decimal_to_units(&decimal("1.0000019"), 6, false),
+            Err(Erc20Error::TooManyDecimals(..))
+        ));
+        assert!(matches!(
+            decimal_to_units(&decimal("-1"), 6, true),
+            Err(Erc20Error::InvalidAmount(_))
+        ));
+        assert!(matches!(
+            decimal_to_units(&decimal(&U256::MAX.to_string()), 1, false),
+            Err(Erc20Error::AmountOverflow(_))
+        ));
+    }
+}
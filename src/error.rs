@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use alloy::primitives::{Bytes, TxHash};
+
 use crate::TokenId;
 
 /// Token related error.
@@ -39,3 +41,173 @@ pub enum InternalError {
     #[error("Failed to decode token: {0}")]
     Sol(#[from] alloy::sol_types::Error),
 }
+
+/// Error returned by [`LazyToken`](crate::LazyToken) methods.
+///
+/// Every method querying the chain can fail with [`Erc20Error::Rpc`],
+/// [`Erc20Error::Reverted`], [`Erc20Error::MissingMethod`] or
+/// [`Erc20Error::Decode`]. The other variants document the methods
+/// returning them.
+#[derive(thiserror::Error, Debug)]
+pub enum Erc20Error {
+    /// The RPC request failed, e.g. because of a timeout or a node error.
+    #[error("RPC error: {0}")]
+    Rpc(#[from] alloy::transports::TransportError),
+    /// The call reverted, with the given revert data, which is empty if no
+    /// reason was given.
+    #[error("The call reverted: {0}")]
+    Reverted(Bytes),
+    /// The called method returned no data, which means the token doesn't
+    /// implement it, or isn't a contract.
+    #[error("The token doesn't implement {0}")]
+    MissingMethod(String),
+    /// The returned data couldn't be decoded.
+    #[error("Failed to decode the returned data: {0}")]
+    Decode(#[from] alloy::dyn_abi::Error),
+    /// The token reports more decimals than the maximum set with
+    /// [`LazyToken::with_max_decimals`](crate::LazyToken::with_max_decimals),
+    /// which would make scaled amounts meaningless.
+    ///
+    /// Returned by [`LazyToken::decimals`](crate::LazyToken::decimals) and
+    /// the methods scaling amounts by the decimals.
+    #[error("The token reports implausible decimals: {0}")]
+    ImplausibleDecimals(u8),
+    /// The amount isn't a valid non-negative decimal number.
+    ///
+    /// Returned by [`LazyToken::parse_amount`](crate::LazyToken::parse_amount)
+    /// and [`LazyToken::to_base_units`](crate::LazyToken::to_base_units).
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    /// The amount has more fractional digits than the token decimals.
+    ///
+    /// Returned by [`LazyToken::parse_amount`](crate::LazyToken::parse_amount)
+    /// and [`LazyToken::to_base_units`](crate::LazyToken::to_base_units).
+    #[error("The amount {0} has more than {1} fractional digits")]
+    TooManyDecimals(String, u8),
+    /// The amount in base units overflows [`U256`](alloy::primitives::U256),
+    /// or [`I256`](alloy::primitives::I256) for signed amounts.
+    ///
+    /// Returned by [`LazyToken::parse_amount`](crate::LazyToken::parse_amount),
+    /// [`LazyToken::to_base_units`](crate::LazyToken::to_base_units),
+    /// [`LazyToken::increase_allowance_fallback`](crate::LazyToken::increase_allowance_fallback)
+    /// and [`LazyToken::supply_deltas`](crate::LazyToken::supply_deltas).
+    #[error("The amount {0} overflows")]
+    AmountOverflow(String),
+    /// The result of subtracting two [`TokenAmount`](crate::TokenAmount)s
+    /// is negative.
+    #[error("The amount {0} is negative")]
+    AmountUnderflow(String),
+    /// Two [`TokenAmount`](crate::TokenAmount)s with different decimals,
+    /// i.e. of different tokens, were combined.
+    #[error("Amounts with {0} and {1} decimals can't be combined")]
+    DecimalsMismatch(u8, u8),
+    /// The simulated transfer returned `false`.
+    ///
+    /// Returned by
+    /// [`LazyToken::transfer_checked`](crate::LazyToken::transfer_checked)
+    /// and
+    /// [`LazyToken::transfer_and_call`](crate::LazyToken::transfer_and_call).
+    #[error("The transfer returned false")]
+    TransferReturnedFalse,
+    /// The token doesn't expose the given non-standard method.
+    ///
+    /// Returned by [`LazyToken::mint`](crate::LazyToken::mint) and
+    /// [`LazyToken::burn`](crate::LazyToken::burn).
+    #[error("The token doesn't support {0}")]
+    UnsupportedOperation(String),
+    /// The permit deadline is already in the past.
+    ///
+    /// Returned by [`PermitParams::validate`](crate::PermitParams::validate)
+    /// and [`sign_permit_checked`](crate::signing::sign_permit_checked).
+    #[error("The deadline {0} has expired")]
+    DeadlineExpired(alloy::primitives::U256),
+    /// The domain separator of the token, the second one, differs from the
+    /// one expected, the first one, so permits signed for the expected
+    /// domain would be rejected.
+    ///
+    /// Returned by
+    /// [`LazyToken::assert_domain_matches`](crate::LazyToken::assert_domain_matches).
+    #[error("The domain separator {1} differs from the expected {0}")]
+    DomainMismatch(
+        alloy::primitives::FixedBytes<32>,
+        alloy::primitives::FixedBytes<32>,
+    ),
+    /// The signer failed to sign.
+    ///
+    /// Returned by
+    /// [`sign_permit_checked`](crate::signing::sign_permit_checked).
+    #[error("Failed to sign: {0}")]
+    Signer(#[from] alloy::signers::Error),
+    /// The transaction was mined but reverted.
+    ///
+    /// Returned by the methods sending transactions.
+    #[error("The transaction {0} reverted")]
+    TransactionReverted(TxHash),
+    /// The transaction wasn't confirmed before the
+    /// [`TxOptions::timeout`](crate::TxOptions::timeout).
+    ///
+    /// Returned by the methods sending transactions.
+    #[error("The transaction {0} wasn't confirmed in time")]
+    TimedOut(TxHash),
+    /// The ENS name couldn't be resolved.
+    ///
+    /// Returned by [`LazyToken::from_ens`](crate::LazyToken::from_ens).
+    #[error("Failed to resolve ENS name: {0}")]
+    Ens(#[from] alloy::ens::EnsError),
+    /// The ENS name doesn't resolve to an address.
+    ///
+    /// Returned by [`LazyToken::from_ens`](crate::LazyToken::from_ens).
+    #[error("The ENS name {0} doesn't resolve to an address")]
+    UnresolvedEnsName(String),
+    /// The environment variable isn't set.
+    ///
+    /// Returned by [`LazyToken::from_env`](crate::LazyToken::from_env).
+    #[error("The environment variable {0} is not set")]
+    MissingEnvVar(String),
+    /// The environment variable can't be parsed.
+    ///
+    /// Returned by [`LazyToken::from_env`](crate::LazyToken::from_env).
+    #[error("The environment variable {0} is invalid: {1}")]
+    InvalidEnvVar(String, String),
+    /// No price feed was set with
+    /// [`LazyToken::with_price_feed`](crate::LazyToken::with_price_feed).
+    ///
+    /// Returned by [`LazyToken::price_usd`](crate::LazyToken::price_usd).
+    #[cfg(feature = "chainlink")]
+    #[error("No price feed is set")]
+    MissingPriceFeed,
+    /// The price feed returned a negative price.
+    ///
+    /// Returned by [`LazyToken::price_usd`](crate::LazyToken::price_usd).
+    #[cfg(feature = "chainlink")]
+    #[error("The price feed returned the negative price {0}")]
+    NegativePrice(alloy::primitives::I256),
+    /// Any other contract error, e.g. while awaiting a pending transaction.
+    #[error("Contract error: {0}")]
+    Contract(alloy::contract::Error),
+}
+
+impl From<alloy::contract::Error> for Erc20Error {
+    fn from(err: alloy::contract::Error) -> Self {
+        use alloy::contract::Error;
+
+        match err {
+            Error::ZeroData(method, _) => Self::MissingMethod(method),
+            Error::AbiError(err) => Self::Decode(err),
+            Error::TransportError(err) if is_revert(&err) => Self::Reverted(
+                err.as_error_resp()
+                    .and_then(|payload| payload.as_revert_data())
+                    .unwrap_or_default(),
+            ),
+            Error::TransportError(err) => Self::Rpc(err),
+            err => Self::Contract(err),
+        }
+    }
+}
+
+/// Returns whether the node rejected a call because it reverted, rather
+/// than failing to execute it.
+pub(crate) fn is_revert(err: &alloy::transports::TransportError) -> bool {
+    err.as_error_resp()
+        .is_some_and(|payload| payload.message.contains("revert"))
+}
@@ -1,28 +1,84 @@
-use crate::provider::Erc20Contract;
+#[cfg(feature = "chainlink")]
+use crate::provider::AggregatorV3;
+use crate::{
+    amount,
+    multicall::{self, MULTICALL3_ADDRESS},
+    provider::{
+        Erc20AllowanceDelta, Erc20Bytes32Metadata, Erc20Contract, Erc20MintBurn, Erc20Permit,
+        Erc677,
+    },
+    signing, AccountSnapshot, ApprovalEvent, Erc20Error, PartialMetadata, RetryPolicy, TokenAmount,
+    TokenMetadata, TransferEvent, TxOptions,
+};
 use alloy::{
-    contract::Error,
-    network::Network,
-    primitives::{Address, U256},
-    providers::Provider,
+    consensus::BlockHeader,
+    contract::{Error, Event, SolCallBuilder},
+    eips::{BlockId, BlockNumberOrTag},
+    ens::ProviderEnsExt,
+    network::{BlockResponse, Ethereum, Network, ReceiptResponse},
+    primitives::{address, uint, Address, Bytes, FixedBytes, I256, U256},
+    providers::{
+        DynProvider, PendingTransactionBuilder, PendingTransactionError, Provider, ProviderBuilder,
+        WalletProvider, WatchTxError,
+    },
+    rpc::types::{
+        simulate::{SimBlock, SimCallResult, SimulatePayload},
+        Log, TransactionRequest,
+    },
+    sol_types::{SolCall, SolEvent},
+    transports::{http::reqwest::Url, RpcError},
 };
 use async_once_cell::OnceCell;
 use bigdecimal::{
     num_bigint::{BigInt, Sign},
     BigDecimal,
 };
-use futures::TryFutureExt;
+use futures::{Stream, StreamExt, TryFutureExt, TryStreamExt};
 use std::{
+    collections::BTreeMap,
+    env::VarError,
     fmt::Debug,
-    future::{ready, IntoFuture},
+    future::{ready, Future},
+    sync::{Arc, Mutex, PoisonError, RwLock},
+    time::{Duration, Instant},
 };
 
+/// Default maximum number of calls aggregated in a single Multicall3 request.
+const DEFAULT_MULTICALL_BATCH_SIZE: usize = 500;
+
+/// Default maximum decimals accepted from a token.
+const DEFAULT_MAX_DECIMALS: u8 = 36;
+
+/// Number of blocks covered by a single `eth_getLogs` request, to stay below
+/// the range limits of most providers.
+const LOG_QUERY_WINDOW: u64 = 2000;
+
+/// The EIP-1967 storage slot holding the implementation address of a proxy,
+/// i.e. `keccak256("eip1967.proxy.implementation") - 1`.
+const EIP1967_IMPLEMENTATION_SLOT: U256 =
+    uint!(0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc_U256);
+
+/// Recipient of the transfers simulated to detect fees on transfer.
+const FEE_PROBE_RECIPIENT: Address = address!("000000000000000000000000000000000000fee0");
+
 #[derive(Debug)]
 /// A token with an embedded contract instance that lazily query the
 /// blockchain.
 pub struct LazyToken<P, N> {
-    name: OnceCell<String>,
-    symbol: OnceCell<String>,
-    decimals: OnceCell<u8>,
+    name: MetadataCell<String>,
+    symbol: MetadataCell<String>,
+    decimals: MetadataCell<u8>,
+    domain_separator: OnceCell<FixedBytes<32>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    total_supply: Mutex<Option<(Instant, U256)>>,
+    allowances: Mutex<BTreeMap<(Address, Address), (Instant, U256)>>,
+    multicall_batch_size: usize,
+    multicall_address: Option<Address>,
+    max_decimals: u8,
+    tx_options: TxOptions,
+    retry_policy: RetryPolicy,
+    #[cfg(feature = "chainlink")]
+    price_feed: Option<Address>,
     /// The underlying ERC20 contract instance. Exposed publicly to allow
     /// direct access for write operations like `transfer`, `approve`, etc.
     pub instance: Erc20Contract::Erc20ContractInstance<P, N>,
@@ -33,101 +89,1945 @@ where
     P: Provider<N>,
     N: Network,
 {
+    /// The allowance above which [`LazyToken::is_infinite_allowance`]
+    /// considers an allowance infinite, i.e. `U256::MAX / 2`.
+    pub const INFINITE_ALLOWANCE_THRESHOLD: U256 =
+        U256::from_limbs([u64::MAX, u64::MAX, u64::MAX, u64::MAX >> 1]);
+
     /// Creates a new [`LazyToken`].
     pub const fn new(address: Address, provider: P) -> Self {
         Self {
-            name: OnceCell::new(),
-            symbol: OnceCell::new(),
-            decimals: OnceCell::new(),
+            name: MetadataCell::new(),
+            symbol: MetadataCell::new(),
+            decimals: MetadataCell::new(),
+            domain_separator: OnceCell::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            total_supply: Mutex::new(None),
+            allowances: Mutex::new(BTreeMap::new()),
+            multicall_batch_size: DEFAULT_MULTICALL_BATCH_SIZE,
+            multicall_address: Some(MULTICALL3_ADDRESS),
+            max_decimals: DEFAULT_MAX_DECIMALS,
+            tx_options: TxOptions {
+                confirmations: 1,
+                timeout: None,
+                max_fee_per_gas: None,
+                max_priority_fee_per_gas: None,
+                gas_limit: None,
+            },
+            retry_policy: RetryPolicy::new(1, Duration::ZERO),
+            #[cfg(feature = "chainlink")]
+            price_feed: None,
             instance: Erc20Contract::new(address, provider),
         }
     }
 
+    /// Sets the maximum number of calls aggregated in a single Multicall3
+    /// request by batch methods like [`LazyToken::balances_of`], which
+    /// bounds the call data of each `eth_call`.
+    pub fn with_multicall_batch_size(mut self, batch_size: usize) -> Self {
+        self.multicall_batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the address of the Multicall3 contract used by batch methods
+    /// like [`LazyToken::balances_of`] and [`LazyToken::metadata`], for
+    /// chains where it isn't deployed at the canonical address.
+    ///
+    /// With `None`, the calls are always sent one by one.
+    pub const fn with_multicall_address(mut self, address: Option<Address>) -> Self {
+        self.multicall_address = address;
+        self
+    }
+
+    /// Sets the maximum decimals accepted from the token, 36 by default.
+    ///
+    /// Tokens reporting more decimals fail with
+    /// [`Erc20Error::ImplausibleDecimals`] rather than producing absurd
+    /// scaled amounts.
+    pub const fn with_max_decimals(mut self, max_decimals: u8) -> Self {
+        self.max_decimals = max_decimals;
+        self
+    }
+
+    /// Sets the gas overrides of the transactions sent by methods like
+    /// [`LazyToken::safe_approve`], and the number of confirmations and the
+    /// timeout used to await them.
+    pub const fn with_tx_options(mut self, tx_options: TxOptions) -> Self {
+        self.tx_options = tx_options;
+        self
+    }
+
+    /// Sets the policy used to retry the reads failing with a transport
+    /// error, like [`LazyToken::balance_of`] or [`LazyToken::metadata`].
+    ///
+    /// No read is retried by default. With the default `tokio` feature,
+    /// retries wait with `tokio::time::sleep` and need a Tokio runtime, see
+    /// [`RetryPolicy::sleep`].
+    pub const fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the EIP-712 domain separator of the token, which is then never
+    /// read from the chain, e.g. to prepare permits offline.
+    pub fn with_domain_separator(mut self, domain_separator: FixedBytes<32>) -> Self {
+        self.domain_separator = OnceCell::new_with(domain_separator);
+        self
+    }
+
+    /// Sets the address of the Chainlink USD price feed of the token, used
+    /// by [`LazyToken::price_usd`].
+    #[cfg(feature = "chainlink")]
+    pub const fn with_price_feed(mut self, feed: Address) -> Self {
+        self.price_feed = Some(feed);
+        self
+    }
+
     /// Returns the token contract address.
     pub const fn address(&self) -> &Address {
         self.instance.address()
     }
 
+    const fn bytes32_metadata(&self) -> Erc20Bytes32Metadata::Erc20Bytes32MetadataInstance<&P, N> {
+        Erc20Bytes32Metadata::new(*self.address(), self.instance.provider())
+    }
+
     /// Returns the name of the token.
-    pub async fn name(&self) -> Result<&String, Error> {
+    ///
+    /// Tokens returning the name as a `bytes32`, like MKR, are supported.
+    /// Concurrent calls on a token not cached yet share a single request.
+    pub async fn name(&self) -> Result<String, Erc20Error> {
         self.name
-            .get_or_try_init(
-                self.instance
-                    .name()
-                    .call()
-                    .into_future()
-                    .and_then(|r| ready(Ok(r))),
-            )
+            .get_or_try_init(self.with_retry(|| async {
+                match self.instance.name().call().await {
+                    Err(err @ Error::AbiError(_)) => self
+                        .bytes32_metadata()
+                        .name()
+                        .call()
+                        .await
+                        .map(bytes32_to_string)
+                        .map_err(|_| err),
+                    result => result,
+                }
+                .map_err(Erc20Error::from)
+            }))
             .await
     }
 
     /// Returns the symbol of the token.
-    pub async fn symbol(&self) -> Result<&String, Error> {
+    ///
+    /// Tokens returning the symbol as a `bytes32`, like MKR, are supported.
+    /// Concurrent calls on a token not cached yet share a single request.
+    pub async fn symbol(&self) -> Result<String, Erc20Error> {
         self.symbol
-            .get_or_try_init(
-                self.instance
-                    .symbol()
-                    .call()
-                    .into_future()
-                    .and_then(|r| ready(Ok(r))),
-            )
+            .get_or_try_init(self.with_retry(|| async {
+                match self.instance.symbol().call().await {
+                    Err(err @ Error::AbiError(_)) => self
+                        .bytes32_metadata()
+                        .symbol()
+                        .call()
+                        .await
+                        .map(bytes32_to_string)
+                        .map_err(|_| err),
+                    result => result,
+                }
+                .map_err(Erc20Error::from)
+            }))
             .await
     }
 
     /// Returns the decimals places of the token.
-    pub async fn decimals(&self) -> Result<&u8, Error> {
+    ///
+    /// Concurrent calls on a token not cached yet share a single request.
+    pub async fn decimals(&self) -> Result<u8, Erc20Error> {
         self.decimals
-            .get_or_try_init(
-                self.instance
-                    .decimals()
-                    .call()
-                    .into_future()
-                    .and_then(|r| ready(Ok(r))),
-            )
+            .get_or_try_init(self.with_retry(|| async {
+                let decimals = self.instance.decimals().call().await?;
+
+                self.check_decimals(decimals)
+            }))
             .await
     }
 
+    /// Returns `decimals`, or [`Erc20Error::ImplausibleDecimals`] if it's
+    /// above the maximum set with [`LazyToken::with_max_decimals`].
+    const fn check_decimals(&self, decimals: u8) -> Result<u8, Erc20Error> {
+        if decimals > self.max_decimals {
+            return Err(Erc20Error::ImplausibleDecimals(decimals));
+        }
+
+        Ok(decimals)
+    }
+
+    /// Returns the name, symbol and decimals of the token.
+    ///
+    /// The values that are not cached yet are queried in a single
+    /// Multicall3 request, and then cached so the individual accessors
+    /// don't query the network again. If the token reverts on `name()` or
+    /// `symbol()`, the corresponding field is left empty instead of failing
+    /// the whole call.
+    pub async fn metadata(&self) -> Result<TokenMetadata, Erc20Error> {
+        self.prefetch_metadata().await?;
+
+        // Anything the multicall couldn't fill is queried individually, so
+        // reverts are told apart from transport errors.
+        let name = self.name().await.or_else(empty_on_revert)?;
+        let symbol = self.symbol().await.or_else(empty_on_revert)?;
+        let decimals = self.decimals().await?;
+
+        Ok(TokenMetadata {
+            name,
+            symbol,
+            decimals,
+        })
+    }
+
+    /// Returns the name, symbol and decimals of the token, leaving the
+    /// fields the token reverts on, or doesn't implement, as `None`.
+    ///
+    /// Like [`LazyToken::metadata`], the values are fetched in a single
+    /// Multicall3 request and cached. This is the resilient path for
+    /// scanning arbitrary addresses, some of which may not be standard
+    /// tokens.
+    pub async fn try_metadata(&self) -> Result<PartialMetadata, Erc20Error> {
+        self.prefetch_metadata().await?;
+
+        Ok(PartialMetadata {
+            name: self.name().await.map(Some).or_else(none_on_revert)?,
+            symbol: self.symbol().await.map(Some).or_else(none_on_revert)?,
+            decimals: self.decimals().await.map(Some).or_else(none_on_revert)?,
+        })
+    }
+
+    /// Caches the name, symbol and decimals not cached yet, querying them
+    /// in a single Multicall3 request.
+    ///
+    /// The values the multicall can't decode are left uncached.
+    async fn prefetch_metadata(&self) -> Result<(), Erc20Error> {
+        if self.name.get().is_none() || self.symbol.get().is_none() || self.decimals.get().is_none()
+        {
+            let calls = vec![
+                multicall::call3(*self.address(), &Erc20Contract::nameCall {}, true),
+                multicall::call3(*self.address(), &Erc20Contract::symbolCall {}, true),
+                multicall::call3(*self.address(), &Erc20Contract::decimalsCall {}, true),
+            ];
+
+            let results = self
+                .with_retry(|| {
+                    multicall::aggregate3(
+                        self.instance.provider(),
+                        self.multicall_address,
+                        calls.clone(),
+                    )
+                    .map_err(Erc20Error::from)
+                })
+                .await?;
+
+            if let Some(results) = results {
+                if let [name, symbol, decimals] = results.as_slice() {
+                    let name =
+                        multicall::try_decode::<Erc20Contract::nameCall>(name).or_else(|| {
+                            multicall::try_decode::<Erc20Bytes32Metadata::nameCall>(name)
+                                .map(bytes32_to_string)
+                        });
+                    if let Some(name) = name {
+                        self.name.get_or_init(name).await;
+                    }
+
+                    let symbol = multicall::try_decode::<Erc20Contract::symbolCall>(symbol)
+                        .or_else(|| {
+                            multicall::try_decode::<Erc20Bytes32Metadata::symbolCall>(symbol)
+                                .map(bytes32_to_string)
+                        });
+                    if let Some(symbol) = symbol {
+                        self.symbol.get_or_init(symbol).await;
+                    }
+
+                    if let Some(decimals) =
+                        multicall::try_decode::<Erc20Contract::decimalsCall>(decimals)
+                            .filter(|decimals| *decimals <= self.max_decimals)
+                    {
+                        self.decimals.get_or_init(decimals).await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of the cached name, symbol and decimals, or `None` if
+    /// any of them isn't cached yet, without querying the network.
+    pub fn cached_metadata(&self) -> Option<TokenMetadata> {
+        Some(TokenMetadata {
+            name: self.name.get()?,
+            symbol: self.symbol.get()?,
+            decimals: self.decimals.get()?,
+        })
+    }
+
+    /// Clears the cached name, so the next call to [`LazyToken::name`]
+    /// queries the network again.
+    pub fn invalidate_name(&self) {
+        self.name.reset();
+    }
+
+    /// Clears the cached symbol, so the next call to [`LazyToken::symbol`]
+    /// queries the network again.
+    pub fn invalidate_symbol(&self) {
+        self.symbol.reset();
+    }
+
+    /// Clears the cached decimals, so the next call to
+    /// [`LazyToken::decimals`] queries the network again.
+    pub fn invalidate_decimals(&self) {
+        self.decimals.reset();
+    }
+
+    /// Replaces the cached decimals with `decimals`, for tokens reporting
+    /// wrong decimals, so [`LazyToken::decimals`] and the methods scaling
+    /// amounts use it instead of querying the network.
+    ///
+    /// [`LazyToken::refresh_metadata`] and [`LazyToken::invalidate_decimals`]
+    /// clear the override.
+    pub fn set_decimals_override(&self, decimals: u8) {
+        self.decimals.set(decimals);
+    }
+
+    /// Re-fetches the name, symbol and decimals of the token, replacing the
+    /// cached values.
+    ///
+    /// On error, the values that couldn't be fetched are left uncached, so
+    /// the next accessor call retries.
+    pub async fn refresh_metadata(&self) -> Result<(), Erc20Error> {
+        self.invalidate_name();
+        self.invalidate_symbol();
+        self.invalidate_decimals();
+
+        futures::try_join!(self.name(), self.symbol(), self.decimals())?;
+
+        Ok(())
+    }
+
     /// Returns the amount of tokens in existence.
-    pub async fn total_supply(&self) -> Result<U256, Error> {
-        self.instance
-            .totalSupply()
-            .call()
-            .into_future()
-            .and_then(|r| ready(Ok(r)))
-            .await
+    pub async fn total_supply(&self) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .totalSupply()
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the amount of tokens in existence, reusing the last value
+    /// fetched by this method if it's more recent than `ttl`.
+    ///
+    /// Use [`LazyToken::total_supply`] to always query the network. Not
+    /// available on `wasm32` targets, where [`Instant::now`] panics.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn total_supply_cached(&self, ttl: Duration) -> Result<U256, Erc20Error> {
+        let cached = *self
+            .total_supply
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some((fetched_at, total_supply)) = cached {
+            if fetched_at.elapsed() < ttl {
+                return Ok(total_supply);
+            }
+        }
+
+        let total_supply = self.total_supply().await?;
+        *self
+            .total_supply
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some((Instant::now(), total_supply));
+
+        Ok(total_supply)
+    }
+
+    /// Returns the amount of tokens in existence at the given `block`.
+    pub async fn total_supply_at(&self, block: BlockId) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .totalSupply()
+                .block(block)
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
     }
 
     /// Returns the value of tokens owned by `account`.
-    pub async fn balance_of(&self, account: Address) -> Result<U256, Error> {
-        self.instance
-            .balanceOf(account)
-            .call()
-            .into_future()
-            .and_then(|r| ready(Ok(r)))
-            .await
+    pub async fn balance_of(&self, account: Address) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .balanceOf(account)
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the value of tokens owned by `account` at the given `block`.
+    pub async fn balance_of_at(
+        &self,
+        account: Address,
+        block: BlockId,
+    ) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .balanceOf(account)
+                .block(block)
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the value of tokens owned by each of `accounts`, in the same
+    /// order.
+    ///
+    /// The `balanceOf` calls are aggregated through Multicall3, split into
+    /// requests of at most [`LazyToken::with_multicall_batch_size`] calls.
+    /// If Multicall3 is not deployed on the chain, the balances are queried
+    /// one by one instead.
+    pub async fn balances_of(&self, accounts: &[Address]) -> Result<Vec<U256>, Erc20Error> {
+        let calls = accounts
+            .iter()
+            .map(|account| Erc20Contract::balanceOfCall { _owner: *account })
+            .collect::<Vec<_>>();
+
+        self.batch_call(&calls).await
+    }
+
+    /// Returns the largest amount `account` can transfer, i.e. its balance
+    /// minus `reserve_gas_wei`.
+    ///
+    /// The reserve is only relevant when the token also pays for gas, e.g.
+    /// the native token of some chains, so the transfer can still be paid
+    /// for. Pass zero for regular tokens, which only return the balance.
+    pub async fn max_transferable(
+        &self,
+        account: Address,
+        reserve_gas_wei: U256,
+    ) -> Result<U256, Erc20Error> {
+        let balance = self.balance_of(account).await?;
+
+        Ok(balance.saturating_sub(reserve_gas_wei))
     }
 
     /// Returns the remaining number of tokens that `spender` will be
     /// allowed to spend on behalf of `owner`.
-    pub async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Error> {
-        self.instance
-            .allowance(owner, spender)
-            .call()
-            .into_future()
-            .and_then(|r| ready(Ok(r)))
+    pub async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .allowance(owner, spender)
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the remaining number of tokens that `spender` will be
+    /// allowed to spend on behalf of `owner`, reusing the last value
+    /// fetched by this method for the pair if it's more recent than `ttl`.
+    ///
+    /// The approval methods of this token, like [`LazyToken::safe_approve`],
+    /// clear the cached value. Approvals sent by other means should be
+    /// followed by [`LazyToken::invalidate_allowance`].
+    ///
+    /// Not available on `wasm32` targets, where [`Instant::now`] panics.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn allowance_cached(
+        &self,
+        owner: Address,
+        spender: Address,
+        ttl: Duration,
+    ) -> Result<U256, Erc20Error> {
+        let cached = self
+            .allowances
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(owner, spender))
+            .copied();
+
+        if let Some((fetched_at, allowance)) = cached {
+            if fetched_at.elapsed() < ttl {
+                return Ok(allowance);
+            }
+        }
+
+        let allowance = self.allowance(owner, spender).await?;
+        self.allowances
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((owner, spender), (Instant::now(), allowance));
+
+        Ok(allowance)
+    }
+
+    /// Clears the allowance of `spender` on behalf of `owner` cached by
+    /// [`LazyToken::allowance_cached`].
+    pub fn invalidate_allowance(&self, owner: Address, spender: Address) {
+        self.allowances
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(owner, spender));
+    }
+
+    /// Returns whether `spender` has an effectively infinite allowance on
+    /// behalf of `owner`.
+    ///
+    /// Many tokens decrement even a `U256::MAX` allowance on each transfer,
+    /// so any allowance above [`LazyToken::INFINITE_ALLOWANCE_THRESHOLD`] is
+    /// considered infinite.
+    pub async fn is_infinite_allowance(
+        &self,
+        owner: Address,
+        spender: Address,
+    ) -> Result<bool, Erc20Error> {
+        let allowance = self.allowance(owner, spender).await?;
+
+        Ok(allowance > Self::INFINITE_ALLOWANCE_THRESHOLD)
+    }
+
+    /// Returns the allowances of each of `spenders` on behalf of `owner`,
+    /// in the same order.
+    ///
+    /// Like [`LazyToken::balances_of`], the `allowance` calls are aggregated
+    /// through Multicall3 when it's deployed on the chain.
+    pub async fn allowances(
+        &self,
+        owner: Address,
+        spenders: &[Address],
+    ) -> Result<Vec<U256>, Erc20Error> {
+        let calls = spenders
+            .iter()
+            .map(|spender| Erc20Contract::allowanceCall {
+                _owner: owner,
+                _spender: *spender,
+            })
+            .collect::<Vec<_>>();
+
+        self.batch_call(&calls).await
+    }
+
+    /// Returns the spenders among `spenders` having a non-zero allowance on
+    /// behalf of `owner`, along with their allowance.
+    pub async fn nonzero_allowances(
+        &self,
+        owner: Address,
+        spenders: &[Address],
+    ) -> Result<Vec<(Address, U256)>, Erc20Error> {
+        let allowances = self.allowances(owner, spenders).await?;
+
+        Ok(spenders
+            .iter()
+            .copied()
+            .zip(allowances)
+            .filter(|(_, allowance)| !allowance.is_zero())
+            .collect())
+    }
+
+    async fn batch_call<C: SolCall>(&self, calls: &[C]) -> Result<Vec<C::Return>, Erc20Error> {
+        self.with_retry(|| {
+            multicall::batch_call(
+                self.instance.provider(),
+                self.multicall_address,
+                self.multicall_batch_size,
+                *self.address(),
+                calls,
+            )
+            .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Runs the read `f`, retrying it according to the [`RetryPolicy`]
+    /// while it fails with a transport error.
+    async fn with_retry<F, Fut, T>(&self, mut f: F) -> Result<T, Erc20Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Erc20Error>>,
+    {
+        let mut backoff = self.retry_policy.backoff;
+
+        for _ in 1..self.retry_policy.attempts {
+            match f().await {
+                Err(Erc20Error::Rpc(_)) => {
+                    (self.retry_policy.sleep)(backoff).await;
+                    backoff = backoff.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+
+        f().await
+    }
+
+    /// Returns whether the token charges a fee on transfer, by simulating
+    /// a transfer of `probe_amount` tokens from `holder` with
+    /// `eth_simulateV1`, and comparing the amount received with the amount
+    /// sent.
+    ///
+    /// The simulation runs against the current state, so `holder` must own
+    /// at least `probe_amount` tokens, otherwise the transfer reverts with
+    /// [`Erc20Error::Reverted`]. Tokens charging fees only on some
+    /// transfers, e.g. to or from a DEX pair, may not be detected, and
+    /// rebasing tokens may be reported as charging a fee.
+    pub async fn detect_fee_on_transfer_from(
+        &self,
+        holder: Address,
+        probe_amount: U256,
+    ) -> Result<bool, Erc20Error> {
+        let call = |from, input: Vec<u8>| {
+            TransactionRequest::default()
+                .from(from)
+                .to(*self.address())
+                .input(input.into())
+        };
+        let balance_of = Erc20Contract::balanceOfCall {
+            _owner: FEE_PROBE_RECIPIENT,
+        }
+        .abi_encode();
+        let transfer = Erc20Contract::transferCall {
+            _to: FEE_PROBE_RECIPIENT,
+            _value: probe_amount,
+        }
+        .abi_encode();
+
+        let payload = SimulatePayload::default().extend(
+            SimBlock::default()
+                .call(call(FEE_PROBE_RECIPIENT, balance_of.clone()))
+                .call(call(holder, transfer))
+                .call(call(FEE_PROBE_RECIPIENT, balance_of)),
+        );
+        let blocks = self.instance.provider().simulate(&payload).await?;
+
+        let Some([before, transfer, after]) = blocks.first().map(|block| block.calls.as_slice())
+        else {
+            return Err(Erc20Error::Rpc(RpcError::NullResp));
+        };
+
+        if !transfer.status {
+            return Err(Erc20Error::Reverted(transfer.return_data.clone()));
+        }
+
+        let decode_balance = |result: &SimCallResult| {
+            Erc20Contract::balanceOfCall::abi_decode_returns(&result.return_data)
+                .map_err(|err| Erc20Error::Decode(err.into()))
+        };
+        let received = decode_balance(after)?.saturating_sub(decode_balance(before)?);
+
+        Ok(received < probe_amount)
+    }
+
+    /// Returns whether the function `selector` appears in the token
+    /// bytecode, or in the one of its implementation for EIP-1967 proxies,
+    /// e.g. to detect non-standard methods before calling them.
+    ///
+    /// This is a heuristic: the 4 bytes may appear in the bytecode without
+    /// being a dispatched selector, and proxies other than EIP-1967 ones
+    /// aren't followed.
+    pub async fn has_selector(&self, selector: [u8; 4]) -> Result<bool, Erc20Error> {
+        if self.code_has_selector(*self.address(), selector).await? {
+            return Ok(true);
+        }
+
+        match self.implementation_address().await? {
+            Some(implementation) => self.code_has_selector(implementation, selector).await,
+            None => Ok(false),
+        }
+    }
+
+    async fn code_has_selector(
+        &self,
+        address: Address,
+        selector: [u8; 4],
+    ) -> Result<bool, Erc20Error> {
+        let code = self
+            .with_retry(|| async {
+                self.instance
+                    .provider()
+                    .get_code_at(address)
+                    .await
+                    .map_err(Erc20Error::from)
+            })
+            .await?;
+
+        Ok(code
+            .windows(selector.len())
+            .any(|window| window == selector))
+    }
+
+    /// Fails with [`Erc20Error::UnsupportedOperation`] unless the selector of
+    /// `C` is found by [`LazyToken::has_selector`].
+    async fn ensure_supports<C: SolCall>(&self) -> Result<(), Erc20Error> {
+        if !self.has_selector(C::SELECTOR).await? {
+            return Err(Erc20Error::UnsupportedOperation(C::SIGNATURE.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the implementation address of the token if it's an EIP-1967
+    /// proxy, like USDC, or `None` if the implementation slot is empty.
+    pub async fn implementation_address(&self) -> Result<Option<Address>, Erc20Error> {
+        let slot = self
+            .with_retry(|| async {
+                self.instance
+                    .provider()
+                    .get_storage_at(*self.address(), EIP1967_IMPLEMENTATION_SLOT)
+                    .await
+                    .map_err(Erc20Error::from)
+            })
+            .await?;
+        let implementation = Address::from_word(slot.into());
+
+        Ok((!implementation.is_zero()).then_some(implementation))
+    }
+
+    /// Returns the EIP-2612 permit nonce of `owner`.
+    pub async fn nonces(&self, owner: Address) -> Result<U256, Erc20Error> {
+        self.with_retry(|| async {
+            self.permit()
+                .nonces(owner)
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the EIP-712 domain separator of the token, used to sign
+    /// EIP-2612 permits.
+    ///
+    /// The separator is read from the chain once and cached, unless set
+    /// with [`LazyToken::with_domain_separator`].
+    pub async fn domain_separator(&self) -> Result<FixedBytes<32>, Erc20Error> {
+        self.domain_separator
+            .get_or_try_init(self.read_domain_separator())
             .await
+            .copied()
+    }
+
+    async fn read_domain_separator(&self) -> Result<FixedBytes<32>, Erc20Error> {
+        self.with_retry(|| async {
+            self.permit()
+                .DOMAIN_SEPARATOR()
+                .call()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Returns the id of the chain the token is deployed on, as reported by
+    /// the provider.
+    pub async fn chain_id(&self) -> Result<u64, Erc20Error> {
+        self.with_retry(|| async {
+            self.instance
+                .provider()
+                .get_chain_id()
+                .await
+                .map_err(Erc20Error::from)
+        })
+        .await
+    }
+
+    /// Computes the EIP-712 domain separator of the token from its name, the
+    /// given domain `version`, e.g. `"2"` for USDC, the chain id and the
+    /// token address, without reading `DOMAIN_SEPARATOR()`.
+    ///
+    /// Comparing it with [`LazyToken::domain_separator`] catches a wrong
+    /// name or version before signing permits offline.
+    pub async fn compute_domain_separator(
+        &self,
+        version: &str,
+    ) -> Result<FixedBytes<32>, Erc20Error> {
+        let name = self.name().await?;
+        let chain_id = self.chain_id().await?;
+
+        Ok(signing::build_domain_separator(
+            &name,
+            version,
+            chain_id,
+            *self.address(),
+        ))
+    }
+
+    /// Checks that the domain separator of the token matches the one
+    /// computed from `expected_name`, `expected_version`, the chain id and
+    /// the token address, failing with [`Erc20Error::DomainMismatch`]
+    /// otherwise.
+    ///
+    /// The separator is always read from the chain, even if cached or set
+    /// with [`LazyToken::with_domain_separator`], so this confirms that
+    /// permits signed offline for the expected domain will be accepted.
+    pub async fn assert_domain_matches(
+        &self,
+        expected_name: &str,
+        expected_version: &str,
+    ) -> Result<(), Erc20Error> {
+        let actual = self.read_domain_separator().await?;
+        let chain_id = self.chain_id().await?;
+        let expected = signing::build_domain_separator(
+            expected_name,
+            expected_version,
+            chain_id,
+            *self.address(),
+        );
+
+        if actual != expected {
+            return Err(Erc20Error::DomainMismatch(expected, actual));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the EIP-2612 permit nonce of `owner` and the domain separator
+    /// of the token, i.e. what's needed to fill [`PermitParams`] and sign it
+    /// with [`sign_permit`](crate::signing::sign_permit).
+    ///
+    /// Both are read in a single Multicall3 request, or one by one if
+    /// Multicall3 is not deployed on the chain. Once cached, the domain
+    /// separator isn't read again, see [`LazyToken::domain_separator`].
+    ///
+    /// [`PermitParams`]: crate::PermitParams
+    pub async fn permit_preconditions(
+        &self,
+        owner: Address,
+    ) -> Result<(U256, FixedBytes<32>), Erc20Error> {
+        if let Some(domain_separator) = self.domain_separator.get() {
+            return Ok((self.nonces(owner).await?, *domain_separator));
+        }
+
+        let calls = vec![
+            multicall::call3(*self.address(), &Erc20Permit::noncesCall { owner }, false),
+            multicall::call3(
+                *self.address(),
+                &Erc20Permit::DOMAIN_SEPARATORCall {},
+                false,
+            ),
+        ];
+
+        let results = self
+            .with_retry(|| {
+                multicall::aggregate3(
+                    self.instance.provider(),
+                    self.multicall_address,
+                    calls.clone(),
+                )
+                .map_err(Erc20Error::from)
+            })
+            .await?;
+
+        match results.as_deref() {
+            Some([nonce, domain_separator]) => {
+                let nonce = multicall::decode::<Erc20Permit::noncesCall>(nonce)?;
+                let domain_separator =
+                    multicall::decode::<Erc20Permit::DOMAIN_SEPARATORCall>(domain_separator)?;
+
+                Ok((
+                    nonce,
+                    *self
+                        .domain_separator
+                        .get_or_init(async { domain_separator })
+                        .await,
+                ))
+            }
+            _ => Ok((self.nonces(owner).await?, self.domain_separator().await?)),
+        }
+    }
+
+    /// Returns the balance of `account`, its allowance to `spender` and its
+    /// EIP-2612 permit nonce, read in a single Multicall3 request, or one by
+    /// one if Multicall3 is not deployed on the chain.
+    ///
+    /// The permit nonce is `None` for tokens without EIP-2612 support, i.e.
+    /// whose `nonces` call reverts or returns nothing.
+    pub async fn account_snapshot(
+        &self,
+        account: Address,
+        spender: Address,
+    ) -> Result<AccountSnapshot, Erc20Error> {
+        let calls = vec![
+            multicall::call3(
+                *self.address(),
+                &Erc20Contract::balanceOfCall { _owner: account },
+                false,
+            ),
+            multicall::call3(
+                *self.address(),
+                &Erc20Contract::allowanceCall {
+                    _owner: account,
+                    _spender: spender,
+                },
+                false,
+            ),
+            multicall::call3(
+                *self.address(),
+                &Erc20Permit::noncesCall { owner: account },
+                true,
+            ),
+        ];
+
+        let results = self
+            .with_retry(|| {
+                multicall::aggregate3(
+                    self.instance.provider(),
+                    self.multicall_address,
+                    calls.clone(),
+                )
+                .map_err(Erc20Error::from)
+            })
+            .await?;
+
+        match results.as_deref() {
+            Some([balance, allowance, permit_nonce]) => Ok(AccountSnapshot {
+                balance: multicall::decode::<Erc20Contract::balanceOfCall>(balance)?,
+                allowance: multicall::decode::<Erc20Contract::allowanceCall>(allowance)?,
+                permit_nonce: multicall::try_decode::<Erc20Permit::noncesCall>(permit_nonce),
+            }),
+            _ => Ok(AccountSnapshot {
+                balance: self.balance_of(account).await?,
+                allowance: self.allowance(account, spender).await?,
+                permit_nonce: match self.nonces(account).await {
+                    Ok(nonce) => Some(nonce),
+                    Err(Erc20Error::Reverted(_) | Erc20Error::MissingMethod(_)) => None,
+                    Err(err) => return Err(err),
+                },
+            }),
+        }
+    }
+
+    const fn permit(&self) -> Erc20Permit::Erc20PermitInstance<&P, N> {
+        Erc20Permit::new(*self.address(), self.instance.provider())
     }
 
     /// Gets the token balance as a [`BigDecimal`]
-    pub async fn get_balance(&self, amount: U256) -> Result<BigDecimal, Error> {
+    pub async fn get_balance(&self, amount: U256) -> Result<BigDecimal, Erc20Error> {
         let decimals = self.decimals().await?;
 
-        let balance = BigDecimal::from((
+        Ok(self.get_balance_with_decimals(amount, decimals))
+    }
+
+    /// Gets the token balance as a [`BigDecimal`] using the given
+    /// `decimals` instead of the token ones, for tokens reporting wrong
+    /// decimals.
+    pub fn get_balance_with_decimals(&self, amount: U256, decimals: u8) -> BigDecimal {
+        BigDecimal::from((
             BigInt::from_bytes_be(Sign::Plus, &amount.to_be_bytes::<{ U256::BYTES }>()),
-            *decimals as i64,
-        ));
+            decimals as i64,
+        ))
+    }
+
+    /// Returns the amount of tokens in existence as a [`BigDecimal`], scaled
+    /// by the token decimals.
+    pub async fn total_supply_decimal(&self) -> Result<BigDecimal, Erc20Error> {
+        let total_supply = self.total_supply().await?;
+
+        self.get_balance(total_supply).await
+    }
+
+    /// Returns the value of tokens owned by `account` as a [`BigDecimal`],
+    /// scaled by the token decimals.
+    pub async fn balance_of_decimal(&self, account: Address) -> Result<BigDecimal, Erc20Error> {
+        let balance = self.balance_of(account).await?;
+
+        self.get_balance(balance).await
+    }
+
+    /// Returns the value of tokens owned by `account`, both in base units
+    /// and as a [`BigDecimal`] scaled by the token decimals, reading the
+    /// balance once.
+    pub async fn balance_detailed(
+        &self,
+        account: Address,
+    ) -> Result<(U256, BigDecimal), Erc20Error> {
+        let balance = self.balance_of(account).await?;
+        let decimal = self.get_balance(balance).await?;
+
+        Ok((balance, decimal))
+    }
+
+    /// Returns the USD price of the token, as reported by the latest round
+    /// of the Chainlink feed set with [`LazyToken::with_price_feed`], scaled
+    /// by the feed decimals.
+    ///
+    /// The round timestamps aren't checked, so callers caring about stale
+    /// prices should read the feed directly.
+    #[cfg(feature = "chainlink")]
+    pub async fn price_usd(&self) -> Result<BigDecimal, Erc20Error> {
+        let feed = self.price_feed.ok_or(Erc20Error::MissingPriceFeed)?;
+        let feed = AggregatorV3::new(feed, self.instance.provider());
+
+        let answer = feed.latestRoundData().call().await?.answer;
+        let decimals = feed.decimals().call().await?;
+
+        if answer.is_negative() {
+            return Err(Erc20Error::NegativePrice(answer));
+        }
+
+        Ok(BigDecimal::from((
+            BigInt::from_bytes_be(
+                Sign::Plus,
+                &answer.into_raw().to_be_bytes::<{ U256::BYTES }>(),
+            ),
+            decimals as i64,
+        )))
+    }
+
+    /// Converts a decimal `amount`, e.g. returned by
+    /// [`LazyToken::get_balance`], back into base units using the token
+    /// decimals.
+    ///
+    /// Amounts with more fractional digits than the token decimals are
+    /// rejected, see [`LazyToken::to_base_units_truncated`] to drop them
+    /// instead. Negative amounts and amounts overflowing [`U256`] are
+    /// rejected too.
+    pub async fn to_base_units(&self, amount: &BigDecimal) -> Result<U256, Erc20Error> {
+        self.decimal_to_units(amount, false).await
+    }
+
+    /// Converts a decimal `amount` into base units using the token decimals,
+    /// dropping the fractional digits beyond the token decimals.
+    pub async fn to_base_units_truncated(&self, amount: &BigDecimal) -> Result<U256, Erc20Error> {
+        self.decimal_to_units(amount, true).await
+    }
+
+    async fn decimal_to_units(
+        &self,
+        amount: &BigDecimal,
+        truncate: bool,
+    ) -> Result<U256, Erc20Error> {
+        let decimals = self.decimals().await?;
+
+        amount::decimal_to_units(amount, decimals, truncate)
+    }
+
+    /// Returns a stream of the token `Transfer` events, starting at
+    /// `from_block`.
+    ///
+    /// The new events are read by polling a log filter, which works with
+    /// both HTTP and WebSocket providers. When `from_block` is in the past,
+    /// the events between it and the latest block are queried first, by
+    /// windows of 2000 blocks like in [`LazyToken::get_transfers`].
+    pub async fn transfer_events(
+        &self,
+        from_block: BlockNumberOrTag,
+    ) -> Result<impl Stream<Item = Result<TransferEvent, Erc20Error>>, Erc20Error> {
+        let logs = self.transfer_logs(from_block).await?;
+
+        Ok(logs.map_ok(|(event, log)| transfer_event(event, &log)))
+    }
+
+    /// Returns a stream of the balances of `account`, yielding the new
+    /// balance whenever a `Transfer` event sending tokens to or from it
+    /// changes it.
+    ///
+    /// The events are watched like in [`LazyToken::transfer_events`] from
+    /// the latest block, and the balance is read at the block of each
+    /// matching event, or at the latest block for pending events. Events
+    /// leaving the balance unchanged, like self-transfers or zero-value
+    /// transfers, yield nothing.
+    pub async fn watch_balance(
+        &self,
+        account: Address,
+    ) -> Result<impl Stream<Item = Result<U256, Erc20Error>> + '_, Erc20Error> {
+        let logs = self.transfer_logs(BlockNumberOrTag::Latest).await?;
+        let mut last_balance = None;
+
+        Ok(logs
+            .try_filter(move |(transfer, _)| {
+                ready(transfer.from == account || transfer.to == account)
+            })
+            .and_then(move |(_, log)| {
+                let block = log.block_number.map_or_else(BlockId::latest, BlockId::from);
+                self.balance_of_at(account, block)
+            })
+            .try_filter_map(move |balance| {
+                ready(Ok(
+                    (last_balance.replace(balance) != Some(balance)).then_some(balance)
+                ))
+            }))
+    }
+
+    /// Returns a stream of the decoded token `Transfer` events along with
+    /// their logs, starting at `from_block`.
+    async fn transfer_logs(
+        &self,
+        from_block: BlockNumberOrTag,
+    ) -> Result<impl Stream<Item = Result<(Erc20Contract::Transfer, Log), Erc20Error>>, Erc20Error>
+    {
+        self.backfill_and_watch(self.instance.Transfer_filter(), from_block)
+            .await
+    }
+
+    /// Returns a stream of the `event` logs, starting at `from_block`.
+    ///
+    /// The log filter is installed first, then the logs from `from_block` to
+    /// the latest block are queried, and the polled logs up to that block
+    /// are skipped, so no log is missed nor duplicated in between.
+    async fn backfill_and_watch<E: SolEvent>(
+        &self,
+        event: Event<&P, E, N>,
+        from_block: BlockNumberOrTag,
+    ) -> Result<impl Stream<Item = Result<(E, Log), Erc20Error>>, Erc20Error> {
+        let event = event.from_block(from_block);
+        let poller = event.watch().await?;
+
+        let (backfill, backfilled_to) = match from_block {
+            BlockNumberOrTag::Latest | BlockNumberOrTag::Pending => (Vec::new(), None),
+            from_block => {
+                let from = self.resolve_block_number(from_block).await?;
+                let latest = self.resolve_block_number(BlockNumberOrTag::Latest).await?;
+
+                (self.query_logs(&event, from, latest).await?, Some(latest))
+            }
+        };
+
+        let polled = poller
+            .into_stream()
+            .map(|event| event.map_err(|err| Erc20Error::Decode(err.into())))
+            .try_filter(move |(_, log)| {
+                ready(match (log.block_number, backfilled_to) {
+                    (Some(block), Some(backfilled_to)) => block > backfilled_to,
+                    _ => true,
+                })
+            });
+
+        Ok(futures::stream::iter(backfill.into_iter().map(Ok)).chain(polled))
+    }
+
+    /// Returns the `event` logs between the `from` and `to` blocks
+    /// (inclusive), querying them by windows of 2000 blocks to stay below
+    /// the `eth_getLogs` range limits of most providers.
+    async fn query_logs<E: SolEvent>(
+        &self,
+        event: &Event<&P, E, N>,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<(E, Log)>, Erc20Error> {
+        let mut logs = Vec::new();
+
+        for start in (from..=to).step_by(LOG_QUERY_WINDOW as usize) {
+            let filter = event
+                .filter
+                .clone()
+                .from_block(start)
+                .to_block(to.min(start + LOG_QUERY_WINDOW - 1));
+
+            logs.extend(
+                Event::<_, E, N>::new(event.provider, filter)
+                    .query()
+                    .await?,
+            );
+        }
+
+        Ok(logs)
+    }
+
+    /// Returns the token `Transfer` events between the `from` and `to`
+    /// blocks (inclusive), in block order.
+    ///
+    /// The events can be restricted to a given `sender` and/or `recipient`.
+    /// The block range is split into windows of 2000 blocks to stay below
+    /// the `eth_getLogs` range limits of most providers.
+    pub async fn get_transfers(
+        &self,
+        from: BlockNumberOrTag,
+        to: BlockNumberOrTag,
+        sender: Option<Address>,
+        recipient: Option<Address>,
+    ) -> Result<Vec<TransferEvent>, Erc20Error> {
+        let from = self.resolve_block_number(from).await?;
+        let to = self.resolve_block_number(to).await?;
+        let mut filter = self.instance.Transfer_filter();
+
+        if let Some(sender) = sender {
+            filter = filter.topic1(sender.into_word());
+        }
+        if let Some(recipient) = recipient {
+            filter = filter.topic2(recipient.into_word());
+        }
+
+        Ok(self
+            .query_logs(&filter, from, to)
+            .await?
+            .into_iter()
+            .map(|(event, log)| transfer_event(event, &log))
+            .collect())
+    }
+
+    /// Returns the changes of the total supply between the `from` and `to`
+    /// blocks (inclusive), as `(block, delta)` pairs in block order.
+    ///
+    /// The deltas are read from the `Transfer` events from the zero address,
+    /// i.e. mints, which are positive, and to the zero address, i.e. burns,
+    /// which are negative. Tokens burning by other means, e.g. to a dead
+    /// address, aren't tracked.
+    pub async fn supply_deltas(
+        &self,
+        from: BlockNumberOrTag,
+        to: BlockNumberOrTag,
+    ) -> Result<Vec<(u64, I256)>, Erc20Error> {
+        let from = BlockNumberOrTag::Number(self.resolve_block_number(from).await?);
+        let to = BlockNumberOrTag::Number(self.resolve_block_number(to).await?);
+        let mints = self
+            .get_transfers(from, to, Some(Address::ZERO), None)
+            .await?;
+        let burns = self
+            .get_transfers(from, to, None, Some(Address::ZERO))
+            .await?;
+
+        let signed = |transfer: &TransferEvent| {
+            I256::try_from(transfer.value)
+                .map_err(|_| Erc20Error::AmountOverflow(transfer.value.to_string()))
+        };
+        let mut deltas = mints
+            .iter()
+            .map(|mint| Ok((mint.block, signed(mint)?)))
+            .chain(burns.iter().map(|burn| Ok((burn.block, -signed(burn)?))))
+            .collect::<Result<Vec<_>, Erc20Error>>()?;
+        deltas.sort_by_key(|(block, _)| *block);
+
+        Ok(deltas)
+    }
+
+    /// Returns a stream of the token `Approval` events, starting at
+    /// `from_block`.
+    ///
+    /// Like [`LazyToken::transfer_events`], the new events are read by
+    /// polling a log filter, after querying the past events when
+    /// `from_block` is in the past.
+    pub async fn approval_events(
+        &self,
+        from_block: BlockNumberOrTag,
+    ) -> Result<impl Stream<Item = Result<ApprovalEvent, Erc20Error>>, Erc20Error> {
+        let logs = self
+            .backfill_and_watch(self.instance.Approval_filter(), from_block)
+            .await?;
+
+        Ok(logs.map_ok(|(event, log)| approval_event(event, &log)))
+    }
+
+    /// Returns the token `Approval` events between the `from` and `to`
+    /// blocks (inclusive), in block order.
+    ///
+    /// The events can be restricted to a given `owner`, which is an indexed
+    /// topic. Like [`LazyToken::get_transfers`], the block range is split
+    /// into windows of 2000 blocks.
+    pub async fn get_approvals(
+        &self,
+        from: BlockNumberOrTag,
+        to: BlockNumberOrTag,
+        owner: Option<Address>,
+    ) -> Result<Vec<ApprovalEvent>, Erc20Error> {
+        let from = self.resolve_block_number(from).await?;
+        let to = self.resolve_block_number(to).await?;
+        let mut filter = self.instance.Approval_filter();
+
+        if let Some(owner) = owner {
+            filter = filter.topic1(owner.into_word());
+        }
+
+        Ok(self
+            .query_logs(&filter, from, to)
+            .await?
+            .into_iter()
+            .map(|(event, log)| approval_event(event, &log))
+            .collect())
+    }
+
+    async fn resolve_block_number(&self, block: BlockNumberOrTag) -> Result<u64, Erc20Error> {
+        let provider = self.instance.provider();
+
+        match block {
+            BlockNumberOrTag::Number(number) => Ok(number),
+            BlockNumberOrTag::Earliest => Ok(0),
+            BlockNumberOrTag::Latest => Ok(provider.get_block_number().await?),
+            tag => provider
+                .get_block_by_number(tag)
+                .await?
+                .map(|block| block.header().number())
+                .ok_or(Erc20Error::Rpc(RpcError::NullResp)),
+        }
+    }
+
+    /// Wraps `raw` base units into a [`TokenAmount`] using the token
+    /// decimals.
+    pub async fn amount(&self, raw: U256) -> Result<TokenAmount, Erc20Error> {
+        let decimals = self.decimals().await?;
+
+        Ok(TokenAmount::new(raw, decimals))
+    }
+
+    /// Formats `amount` as a fixed-point string using the token decimals,
+    /// e.g. `1000000` on a 6 decimals token is formatted as `"1.000000"`.
+    pub async fn format_amount(&self, amount: U256) -> Result<String, Erc20Error> {
+        let decimals = self.decimals().await?;
+
+        Ok(amount::format_units(amount, decimals))
+    }
+
+    /// Parses a user-entered decimal string like `"1,234.5"` into base units
+    /// using the token decimals.
+    ///
+    /// Surrounding whitespace and thousands separators are ignored. Amounts
+    /// with more fractional digits than the token decimals, or overflowing
+    /// [`U256`], are rejected.
+    pub async fn parse_amount(&self, s: &str) -> Result<U256, Erc20Error> {
+        let decimals = self.decimals().await?;
+
+        amount::parse_units(s, decimals)
+    }
+
+    /// Awaits the `pending` transaction until it's mined and `confirmations`
+    /// blocks, including its own, are on top of it, calling `on_update` with
+    /// the number of confirmations so far, 0 until it's mined, each time the
+    /// node is polled.
+    ///
+    /// The receipt and block number are polled at the poll interval of the
+    /// provider, waiting with the sleep function of the [`RetryPolicy`], and
+    /// the [`TxOptions`] timeout fails with [`Erc20Error::TimedOut`]. Like
+    /// the transactions sent by this token, a reverted transaction fails
+    /// with [`Erc20Error::TransactionReverted`].
+    pub async fn wait_with_progress(
+        &self,
+        pending: PendingTransactionBuilder<N>,
+        confirmations: u64,
+        mut on_update: impl FnMut(u64),
+    ) -> Result<N::ReceiptResponse, Erc20Error> {
+        let provider = self.instance.provider();
+        let tx_hash = *pending.tx_hash();
+        let poll_interval = provider.client().poll_interval();
+        let mut waited = Duration::ZERO;
+
+        loop {
+            let receipt = self
+                .with_retry(|| {
+                    provider
+                        .get_transaction_receipt(tx_hash)
+                        .map_err(Erc20Error::from)
+                })
+                .await?;
+
+            let confirmed = match &receipt {
+                Some(receipt) => {
+                    let latest = self.resolve_block_number(BlockNumberOrTag::Latest).await?;
+                    let mined = receipt.block_number().unwrap_or(latest);
+
+                    (latest + 1).saturating_sub(mined)
+                }
+                None => 0,
+            };
+            on_update(confirmed.min(confirmations));
+
+            if let Some(receipt) = receipt.filter(|_| confirmed >= confirmations) {
+                if !receipt.status() {
+                    return Err(Erc20Error::TransactionReverted(tx_hash));
+                }
+
+                return Ok(receipt);
+            }
+
+            if self
+                .tx_options
+                .timeout
+                .is_some_and(|timeout| waited >= timeout)
+            {
+                return Err(Erc20Error::TimedOut(tx_hash));
+            }
+
+            (self.retry_policy.sleep)(poll_interval).await;
+            waited += poll_interval;
+        }
+    }
+}
+
+impl<P, N> LazyToken<P, N>
+where
+    P: Provider<N> + WalletProvider<N>,
+    N: Network,
+{
+    /// Transfers `amount` tokens to `to` from the provider's default
+    /// signer, and returns the amount actually debited from the sender.
+    ///
+    /// The transfer is simulated first, and isn't sent if it returns
+    /// `false`. Tokens returning nothing from `transfer`, like USDT, are
+    /// supported. Once sent, the transfer is awaited until mined, and the
+    /// sender balance is compared to the one before the transfer, so tokens
+    /// charging a fee on transfer or rebasing can be detected by comparing
+    /// the result with `amount`.
+    pub async fn transfer_checked(&self, to: Address, amount: U256) -> Result<U256, Erc20Error> {
+        let from = self.instance.provider().default_signer_address();
+        let transfer = self.instance.transfer(to, amount).from(from);
+
+        let before = self.balance_of(from).await?;
+
+        if !self.simulate(&transfer).await? {
+            return Err(Erc20Error::TransferReturnedFalse);
+        }
+
+        let receipt = self.send_confirmed(transfer).await?;
+
+        let after = match receipt.block_number() {
+            Some(block) => self.balance_of_at(from, block.into()).await,
+            None => self.balance_of(from).await,
+        }?;
+
+        Ok(before.saturating_sub(after))
+    }
+
+    /// Approves `spender` to spend `amount` tokens on behalf of the
+    /// provider's default signer, working around tokens like USDT that
+    /// revert when changing a non-zero allowance to another non-zero value.
+    ///
+    /// If the current allowance is already `amount`, nothing is sent. If it
+    /// is non-zero and `amount` is non-zero too, the allowance is reset to
+    /// zero first, which costs an extra transaction. Each transaction is
+    /// awaited until mined.
+    pub async fn safe_approve(&self, spender: Address, amount: U256) -> Result<(), Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+        let current = self.allowance(owner, spender).await?;
+
+        if current == amount {
+            return Ok(());
+        }
+
+        if !current.is_zero() && !amount.is_zero() {
+            self.send_approval(
+                owner,
+                spender,
+                self.instance.approve(spender, U256::ZERO).from(owner),
+            )
+            .await?;
+        }
+
+        self.send_approval(
+            owner,
+            spender,
+            self.instance.approve(spender, amount).from(owner),
+        )
+        .await
+    }
+
+    /// Approves `spender` to spend an unlimited amount of tokens, i.e.
+    /// `U256::MAX`, on behalf of the provider's default signer, and awaits
+    /// the transaction until mined.
+    ///
+    /// On tokens requiring an allowance reset first, like USDT, use
+    /// [`LazyToken::safe_approve`] with `U256::MAX` instead.
+    pub async fn approve_max(&self, spender: Address) -> Result<(), Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+
+        self.send_approval(
+            owner,
+            spender,
+            self.instance.approve(spender, U256::MAX).from(owner),
+        )
+        .await
+    }
+
+    /// Transfers `value` tokens to the contract `to` from the provider's
+    /// default signer and calls its `onTokenTransfer` hook with `data`,
+    /// through the ERC-677 `transferAndCall` method, like on LINK.
+    ///
+    /// The call is simulated first, and isn't sent if it returns `false`,
+    /// or reverts, e.g. on tokens not implementing ERC-677. Once sent, the
+    /// transaction is awaited until mined.
+    pub async fn transfer_and_call(
+        &self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Erc20Error> {
+        let from = self.instance.provider().default_signer_address();
+        let erc677 = Erc677::new(*self.address(), self.instance.provider());
+        let transfer = erc677.transferAndCall(to, value, data).from(from);
+
+        if !self.simulate(&transfer).await? {
+            return Err(Erc20Error::TransferReturnedFalse);
+        }
+
+        self.send_confirmed(transfer).await?;
+
+        Ok(())
+    }
+
+    /// Mints `amount` tokens to `to` from the provider's default signer,
+    /// through the non-standard `mint(address,uint256)` method exposed by
+    /// test and some application tokens, and awaits the transaction until
+    /// mined.
+    ///
+    /// The token bytecode is checked for the method first, failing with
+    /// [`Erc20Error::UnsupportedOperation`] if it's absent.
+    pub async fn mint(&self, to: Address, amount: U256) -> Result<(), Erc20Error> {
+        self.ensure_supports::<Erc20MintBurn::mintCall>().await?;
+
+        let from = self.instance.provider().default_signer_address();
+        self.send_confirmed(self.mint_burn().mint(to, amount).from(from))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Burns `amount` tokens of the provider's default signer, through the
+    /// non-standard `burn(uint256)` method, and awaits the transaction until
+    /// mined.
+    ///
+    /// Like [`LazyToken::mint`], this fails with
+    /// [`Erc20Error::UnsupportedOperation`] if the token doesn't expose the
+    /// method.
+    pub async fn burn(&self, amount: U256) -> Result<(), Erc20Error> {
+        self.ensure_supports::<Erc20MintBurn::burnCall>().await?;
+
+        let from = self.instance.provider().default_signer_address();
+        self.send_confirmed(self.mint_burn().burn(amount).from(from))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Simulates a transfer of `amount` tokens to `to` from the provider's
+    /// default signer with `eth_call`, without sending it, and returns the
+    /// value `transfer` would return.
+    ///
+    /// Tokens returning nothing from `transfer`, like USDT, are reported as
+    /// `true`. A transfer that would revert fails with
+    /// [`Erc20Error::Reverted`].
+    pub async fn simulate_transfer(&self, to: Address, amount: U256) -> Result<bool, Erc20Error> {
+        let from = self.instance.provider().default_signer_address();
+
+        self.simulate(&self.instance.transfer(to, amount).from(from))
+            .await
+    }
+
+    /// Simulates approving `spender` to spend `amount` tokens on behalf of
+    /// the provider's default signer with `eth_call`, without sending it,
+    /// and returns the value `approve` would return.
+    ///
+    /// Like [`LazyToken::simulate_transfer`], an empty return is reported as
+    /// `true`.
+    pub async fn simulate_approve(
+        &self,
+        spender: Address,
+        amount: U256,
+    ) -> Result<bool, Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+
+        self.simulate(&self.instance.approve(spender, amount).from(owner))
+            .await
+    }
+
+    /// Returns whether the token charges a fee on transfer, by simulating
+    /// a transfer of `probe_amount` tokens from the provider's default
+    /// signer.
+    ///
+    /// See [`LazyToken::detect_fee_on_transfer_from`] for the limitations.
+    pub async fn detect_fee_on_transfer(&self, probe_amount: U256) -> Result<bool, Erc20Error> {
+        let holder = self.instance.provider().default_signer_address();
+
+        self.detect_fee_on_transfer_from(holder, probe_amount).await
+    }
+
+    /// Increases the allowance of `spender` on behalf of the provider's
+    /// default signer by `added`, through the non-standard
+    /// `increaseAllowance` method, and awaits the transaction until mined.
+    ///
+    /// Unlike `approve`, this is safe against the approve front-running race
+    /// condition. For tokens not implementing it, see
+    /// [`LazyToken::increase_allowance_fallback`].
+    pub async fn increase_allowance(
+        &self,
+        spender: Address,
+        added: U256,
+    ) -> Result<(), Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+
+        self.send_approval(
+            owner,
+            spender,
+            self.allowance_delta()
+                .increaseAllowance(spender, added)
+                .from(owner),
+        )
+        .await
+    }
+
+    /// Decreases the allowance of `spender` on behalf of the provider's
+    /// default signer by `subtracted`, through the non-standard
+    /// `decreaseAllowance` method, and awaits the transaction until mined.
+    pub async fn decrease_allowance(
+        &self,
+        spender: Address,
+        subtracted: U256,
+    ) -> Result<(), Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+
+        self.send_approval(
+            owner,
+            spender,
+            self.allowance_delta()
+                .decreaseAllowance(spender, subtracted)
+                .from(owner),
+        )
+        .await
+    }
+
+    /// Increases the allowance of `spender` on behalf of the provider's
+    /// default signer by `added`, for tokens without `increaseAllowance`,
+    /// by reading the current allowance and approving the sum.
+    ///
+    /// This isn't safe against the approve race condition, and on tokens
+    /// like USDT reverting when changing a non-zero allowance, the `approve`
+    /// reverts: use [`LazyToken::safe_approve`] there instead.
+    pub async fn increase_allowance_fallback(
+        &self,
+        spender: Address,
+        added: U256,
+    ) -> Result<(), Erc20Error> {
+        let owner = self.instance.provider().default_signer_address();
+        let current = self.allowance(owner, spender).await?;
+        let amount = current
+            .checked_add(added)
+            .ok_or_else(|| Erc20Error::AmountOverflow(added.to_string()))?;
+
+        self.send_approval(
+            owner,
+            spender,
+            self.instance.approve(spender, amount).from(owner),
+        )
+        .await
+    }
+
+    const fn mint_burn(&self) -> Erc20MintBurn::Erc20MintBurnInstance<&P, N> {
+        Erc20MintBurn::new(*self.address(), self.instance.provider())
+    }
+
+    const fn allowance_delta(&self) -> Erc20AllowanceDelta::Erc20AllowanceDeltaInstance<&P, N> {
+        Erc20AllowanceDelta::new(*self.address(), self.instance.provider())
+    }
+
+    /// Simulates `call`, treating an empty return as success for tokens not
+    /// returning a `bool`, like USDT.
+    async fn simulate<Q, C>(&self, call: &SolCallBuilder<Q, C, N>) -> Result<bool, Erc20Error>
+    where
+        Q: Provider<N>,
+        C: SolCall<Return = bool>,
+    {
+        let data = call.call_raw().await?;
+
+        if data.is_empty() {
+            return Ok(true);
+        }
+
+        C::abi_decode_returns(&data).map_err(|err| Erc20Error::Decode(err.into()))
+    }
+
+    /// Sends the allowance change `call` like [`LazyToken::send_confirmed`],
+    /// and clears the cached allowance of `spender` on behalf of `owner`,
+    /// even if the transaction failed.
+    async fn send_approval<Q, C>(
+        &self,
+        owner: Address,
+        spender: Address,
+        call: SolCallBuilder<Q, C, N>,
+    ) -> Result<(), Erc20Error>
+    where
+        Q: Provider<N>,
+        C: SolCall,
+    {
+        let result = self.send_confirmed(call).await;
+        self.invalidate_allowance(owner, spender);
+
+        result.map(|_| ())
+    }
+
+    /// Sends `call` with the gas overrides of the [`TxOptions`] and awaits
+    /// its receipt according to them, failing if the transaction reverted.
+    async fn send_confirmed<Q, C>(
+        &self,
+        mut call: SolCallBuilder<Q, C, N>,
+    ) -> Result<N::ReceiptResponse, Erc20Error>
+    where
+        Q: Provider<N>,
+        C: SolCall,
+    {
+        if let Some(max_fee_per_gas) = self.tx_options.max_fee_per_gas {
+            call = call.max_fee_per_gas(max_fee_per_gas);
+        }
+        if let Some(max_priority_fee_per_gas) = self.tx_options.max_priority_fee_per_gas {
+            call = call.max_priority_fee_per_gas(max_priority_fee_per_gas);
+        }
+        if let Some(gas_limit) = self.tx_options.gas_limit {
+            call = call.gas(gas_limit);
+        }
+
+        let pending = call
+            .send()
+            .await?
+            .with_required_confirmations(self.tx_options.confirmations)
+            .with_timeout(self.tx_options.timeout);
+        let tx_hash = *pending.tx_hash();
+
+        let receipt = pending.get_receipt().await.map_err(|err| match err {
+            PendingTransactionError::TxWatcher(WatchTxError::Timeout) => {
+                Erc20Error::TimedOut(tx_hash)
+            }
+            err => Error::from(err).into(),
+        })?;
+
+        if !receipt.status() {
+            return Err(Erc20Error::TransactionReverted(receipt.transaction_hash()));
+        }
+
+        Ok(receipt)
+    }
+}
+
+/// Creates a [`LazyToken`] for each of `addresses`, all sharing `provider`.
+pub fn load_tokens<P, N>(addresses: &[Address], provider: P) -> Vec<LazyToken<P, N>>
+where
+    P: Provider<N> + Clone,
+    N: Network,
+{
+    addresses
+        .iter()
+        .map(|address| LazyToken::new(*address, provider.clone()))
+        .collect()
+}
+
+/// Fetches and caches the metadata of each of `tokens` with
+/// [`LazyToken::metadata`], returning the results in the same order.
+///
+/// At most `concurrency` tokens are queried at the same time, to avoid
+/// overwhelming rate-limited endpoints.
+pub async fn load_tokens_metadata<P, N>(
+    tokens: &[LazyToken<P, N>],
+    concurrency: usize,
+) -> Vec<Result<TokenMetadata, Erc20Error>>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    futures::stream::iter(tokens)
+        .map(LazyToken::metadata)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Returns the balances of `account` in each of `tokens`, in the same
+/// order, reading them through the Multicall3 contract at
+/// `multicall_address` when it's deployed on the chain.
+///
+/// Pass [`MULTICALL3_ADDRESS`](alloy::providers::MULTICALL3_ADDRESS) for
+/// the canonical deployment, or `None` to always send the calls one by one,
+/// like [`LazyToken::with_multicall_address`].
+///
+/// The balance is `None` for addresses that aren't tokens, i.e. reverting
+/// or returning no balance, rather than failing the whole query.
+pub async fn balances_across_tokens<P, N>(
+    account: Address,
+    tokens: &[Address],
+    provider: P,
+    multicall_address: Option<Address>,
+) -> Result<Vec<Option<U256>>, Erc20Error>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    let calls = tokens
+        .iter()
+        .map(|token| (*token, Erc20Contract::balanceOfCall { _owner: account }))
+        .collect::<Vec<_>>();
+
+    multicall::try_batch_call(
+        &provider,
+        multicall_address,
+        DEFAULT_MULTICALL_BATCH_SIZE,
+        &calls,
+    )
+    .await
+    .map_err(Erc20Error::from)
+}
+
+fn transfer_event(event: Erc20Contract::Transfer, log: &Log) -> TransferEvent {
+    TransferEvent {
+        from: event.from,
+        to: event.to,
+        value: event.value,
+        block: log.block_number.unwrap_or_default(),
+        tx_hash: log.transaction_hash.unwrap_or_default(),
+    }
+}
+
+fn approval_event(event: Erc20Contract::Approval, log: &Log) -> ApprovalEvent {
+    ApprovalEvent {
+        owner: event.owner,
+        spender: event.spender,
+        value: event.value,
+        block: log.block_number.unwrap_or_default(),
+        tx_hash: log.transaction_hash.unwrap_or_default(),
+    }
+}
+
+/// Converts a `bytes32` name or symbol to a string, trimming the trailing
+/// zero bytes.
+fn bytes32_to_string(bytes: FixedBytes<32>) -> String {
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+impl<P> LazyToken<P, Ethereum>
+where
+    P: Provider<Ethereum>,
+{
+    /// Creates a new [`LazyToken`] from an ENS name like `dai.tokens.eth`,
+    /// resolving it to the token address with `provider`.
+    pub async fn from_ens(name: &str, provider: P) -> Result<Self, Erc20Error> {
+        let address = provider.resolve_name(name).await?;
+
+        if address.is_zero() {
+            return Err(Erc20Error::UnresolvedEnsName(name.to_string()));
+        }
+
+        Ok(Self::new(address, provider))
+    }
+}
+
+impl<P, N> LazyToken<Arc<P>, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    /// Creates a new [`LazyToken`] backed by a shared provider, so a single
+    /// provider and its connection pool serve many tokens.
+    ///
+    /// Alloy providers are cheap to clone already, so [`LazyToken::new`] can
+    /// be given clones of the same provider too, like [`load_tokens`] does.
+    pub const fn new_shared(address: Address, provider: Arc<P>) -> Self {
+        Self::new(address, provider)
+    }
+}
+
+impl LazyToken<DynProvider, Ethereum> {
+    /// Creates a new [`LazyToken`] from the environment, reading the HTTP
+    /// RPC URL from the `rpc_var` variable and the token address from the
+    /// `token_addr_var` variable.
+    ///
+    /// There are no defaults: if a variable is absent,
+    /// [`Erc20Error::MissingEnvVar`] is returned, and if it can't be parsed,
+    /// [`Erc20Error::InvalidEnvVar`].
+    pub fn from_env(rpc_var: &str, token_addr_var: &str) -> Result<Self, Erc20Error> {
+        let rpc_url = env_var(rpc_var)?
+            .parse::<Url>()
+            .map_err(|err| Erc20Error::InvalidEnvVar(rpc_var.to_string(), err.to_string()))?;
+        let address = env_var(token_addr_var)?.parse::<Address>().map_err(|err| {
+            Erc20Error::InvalidEnvVar(token_addr_var.to_string(), err.to_string())
+        })?;
+
+        let provider = ProviderBuilder::new().connect_http(rpc_url).erased();
+
+        Ok(Self::new(address, provider))
+    }
+}
+
+fn env_var(name: &str) -> Result<String, Erc20Error> {
+    std::env::var(name).map_err(|err| match err {
+        VarError::NotPresent => Erc20Error::MissingEnvVar(name.to_string()),
+        VarError::NotUnicode(_) => Erc20Error::InvalidEnvVar(name.to_string(), err.to_string()),
+    })
+}
+
+/// Returns `true` if `err` comes from the contract itself, i.e. a revert,
+/// undecodable or implausible return data, rather than from the transport.
+const fn is_contract_failure(err: &Erc20Error) -> bool {
+    matches!(
+        err,
+        Erc20Error::Reverted(_)
+            | Erc20Error::MissingMethod(_)
+            | Erc20Error::Decode(_)
+            | Erc20Error::ImplausibleDecimals(_)
+    )
+}
+
+/// A cached metadata value, which unlike a bare [`OnceCell`] can be reset
+/// through a shared reference.
+///
+/// Concurrent initializations of the same cell share a single future, and
+/// a reset replaces the cell, so initializations still running complete on
+/// the replaced cell.
+#[derive(Debug)]
+struct MetadataCell<T>(RwLock<Option<Arc<OnceCell<T>>>>);
+
+impl<T: Clone> MetadataCell<T> {
+    const fn new() -> Self {
+        Self(RwLock::new(None))
+    }
+
+    fn cell(&self) -> Arc<OnceCell<T>> {
+        if let Some(cell) = &*self.0.read().unwrap_or_else(PoisonError::into_inner) {
+            return cell.clone();
+        }
+
+        self.0
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert_with(Default::default)
+            .clone()
+    }
+
+    fn get(&self) -> Option<T> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()?
+            .get()
+            .cloned()
+    }
+
+    async fn get_or_init(&self, value: T) -> T {
+        self.cell().get_or_init(ready(value)).await.clone()
+    }
+
+    async fn get_or_try_init<E>(&self, init: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+        self.cell().get_or_try_init(init).await.cloned()
+    }
+
+    fn set(&self, value: T) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) =
+            Some(Arc::new(OnceCell::new_with(value)));
+    }
+
+    fn reset(&self) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+fn empty_on_revert(err: Erc20Error) -> Result<String, Erc20Error> {
+    if is_contract_failure(&err) {
+        Ok(String::new())
+    } else {
+        Err(err)
+    }
+}
 
-        Ok(balance)
+fn none_on_revert<T>(err: Erc20Error) -> Result<Option<T>, Erc20Error> {
+    if is_contract_failure(&err) {
+        Ok(None)
+    } else {
+        Err(err)
     }
 }
 
@@ -17,13 +17,29 @@ mod provider;
 pub use provider::Erc20ProviderExt;
 
 mod error;
-pub use error::Error;
+pub use error::{Erc20Error, Error};
 
 mod token;
 pub use token::Token;
 
+mod amount;
+pub use amount::format_amount_rounded;
+
+mod multicall;
+
 mod lazy_token;
-pub use lazy_token::LazyToken;
+pub use lazy_token::{balances_across_tokens, load_tokens, load_tokens_metadata, LazyToken};
+
+mod read;
+pub use read::{Erc20Read, InstanceReader};
+
+mod types;
+pub use types::{
+    parse_checked_address, to_checksum_string, AccountSnapshot, ApprovalEvent, PartialMetadata,
+    PermitParams, RetryPolicy, TokenAmount, TokenMetadata, TransferEvent, TxOptions,
+};
+
+pub mod signing;
 
 mod token_id;
 pub use token_id::TokenId;
@@ -0,0 +1,178 @@
+use alloy::{
+    contract::Error,
+    network::{Network, TransactionBuilder},
+    primitives::Address,
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+};
+
+use crate::error::is_revert;
+pub(crate) use alloy::providers::MULTICALL3_ADDRESS;
+
+sol!(
+    #[sol(rpc)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+);
+
+/// Sends `calls` through the Multicall3 contract at `address` in a single
+/// `eth_call`.
+///
+/// Returns `Ok(None)` if `address` is `None` or no contract answers at
+/// `address`, so callers can fall back to sequential calls.
+pub(crate) async fn aggregate3<P, N>(
+    provider: &P,
+    address: Option<Address>,
+    calls: Vec<IMulticall3::Call3>,
+) -> Result<Option<Vec<IMulticall3::Result>>, Error>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    let Some(address) = address else {
+        return Ok(None);
+    };
+    let multicall = IMulticall3::new(address, provider);
+
+    match multicall.aggregate3(calls).call().await {
+        Ok(results) => Ok(Some(results)),
+        Err(Error::ZeroData(..)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Sends `calls` to the `target` contract through the Multicall3 contract at
+/// `address`, in requests of at most `batch_size` calls, and returns the
+/// decoded results in order.
+///
+/// If `address` is `None` or no contract answers at it, the calls are sent
+/// one by one instead.
+pub(crate) async fn batch_call<C, P, N>(
+    provider: &P,
+    address: Option<Address>,
+    batch_size: usize,
+    target: Address,
+    calls: &[C],
+) -> Result<Vec<C::Return>, Error>
+where
+    C: SolCall,
+    P: Provider<N>,
+    N: Network,
+{
+    let mut returns = Vec::with_capacity(calls.len());
+
+    for chunk in calls.chunks(batch_size) {
+        let call3s = chunk
+            .iter()
+            .map(|call| call3(target, call, false))
+            .collect();
+
+        match aggregate3(provider, address, call3s).await? {
+            Some(results) => {
+                for result in &results {
+                    returns.push(decode::<C>(result)?);
+                }
+            }
+            None => {
+                for call in chunk {
+                    let tx = N::TransactionRequest::default()
+                        .with_to(target)
+                        .with_input(call.abi_encode());
+                    let data = provider.call(tx).await?;
+
+                    returns.push(C::abi_decode_returns(&data)?);
+                }
+            }
+        }
+    }
+
+    Ok(returns)
+}
+
+/// Sends `calls`, each to its own target contract, through the Multicall3
+/// contract at `address`, in requests of at most `batch_size` calls, and
+/// returns the decoded results in order.
+///
+/// Calls that revert or return undecodable data are returned as `None`
+/// instead of failing the whole batch. If `address` is `None` or no contract
+/// answers at it, the calls are sent one by one instead.
+pub(crate) async fn try_batch_call<C, P, N>(
+    provider: &P,
+    address: Option<Address>,
+    batch_size: usize,
+    calls: &[(Address, C)],
+) -> Result<Vec<Option<C::Return>>, Error>
+where
+    C: SolCall,
+    P: Provider<N>,
+    N: Network,
+{
+    let mut returns = Vec::with_capacity(calls.len());
+
+    for chunk in calls.chunks(batch_size) {
+        let call3s = chunk
+            .iter()
+            .map(|(target, call)| call3(*target, call, true))
+            .collect();
+
+        match aggregate3(provider, address, call3s).await? {
+            Some(results) => returns.extend(results.iter().map(try_decode::<C>)),
+            None => {
+                for (target, call) in chunk {
+                    let tx = N::TransactionRequest::default()
+                        .with_to(*target)
+                        .with_input(call.abi_encode());
+
+                    match provider.call(tx).await {
+                        Ok(data) => returns.push(C::abi_decode_returns(&data).ok()),
+                        Err(err) if is_revert(&err) => returns.push(None),
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(returns)
+}
+
+/// Builds a [`IMulticall3::Call3`] targeting `target`.
+pub(crate) fn call3<C: SolCall>(
+    target: Address,
+    call: &C,
+    allow_failure: bool,
+) -> IMulticall3::Call3 {
+    IMulticall3::Call3 {
+        target,
+        allowFailure: allow_failure,
+        callData: call.abi_encode().into(),
+    }
+}
+
+/// Decodes the return data of a sub-call, or returns `None` if it failed or
+/// can't be decoded as `C`'s return type.
+pub(crate) fn try_decode<C: SolCall>(result: &IMulticall3::Result) -> Option<C::Return> {
+    if result.success {
+        C::abi_decode_returns(&result.returnData).ok()
+    } else {
+        None
+    }
+}
+
+/// Decodes the return data of a successful sub-call.
+pub(crate) fn decode<C: SolCall>(result: &IMulticall3::Result) -> Result<C::Return, Error> {
+    C::abi_decode_returns(&result.returnData).map_err(|err| Error::AbiError(err.into()))
+}
@@ -9,6 +9,54 @@ sol!(
     "abi/erc20.json"
 );
 
+sol!(
+    #[sol(rpc)]
+    interface Erc20Bytes32Metadata {
+        function name() external view returns (bytes32);
+        function symbol() external view returns (bytes32);
+    }
+);
+
+#[cfg(feature = "chainlink")]
+sol!(
+    #[sol(rpc)]
+    interface AggregatorV3 {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    interface Erc20MintBurn {
+        function mint(address to, uint256 amount) external;
+        function burn(uint256 amount) external;
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    interface Erc20Permit {
+        function nonces(address owner) external view returns (uint256);
+        function DOMAIN_SEPARATOR() external view returns (bytes32);
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    interface Erc677 {
+        function transferAndCall(address to, uint256 value, bytes data) external returns (bool);
+    }
+);
+
+sol!(
+    #[sol(rpc)]
+    interface Erc20AllowanceDelta {
+        function increaseAllowance(address spender, uint256 addedValue) external returns (bool);
+        function decreaseAllowance(address spender, uint256 subtractedValue) external returns (bool);
+    }
+);
+
 /// Extends Alloy [`Provider`] trait with ERC-20 related features.
 #[async_trait]
 pub trait Erc20ProviderExt<N>: Provider<N> + Sized
@@ -0,0 +1,111 @@
+use crate::{provider::Erc20Contract::Erc20ContractInstance, Erc20Error, LazyToken};
+use alloy::{
+    network::Network,
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use async_trait::async_trait;
+
+/// The ERC-20 read methods, so utilities can be written against any token
+/// type.
+#[async_trait]
+pub trait Erc20Read {
+    /// Returns the name of the token.
+    async fn name(&self) -> Result<String, Erc20Error>;
+
+    /// Returns the symbol of the token.
+    async fn symbol(&self) -> Result<String, Erc20Error>;
+
+    /// Returns the decimals places of the token.
+    async fn decimals(&self) -> Result<u8, Erc20Error>;
+
+    /// Returns the amount of tokens in existence.
+    async fn total_supply(&self) -> Result<U256, Erc20Error>;
+
+    /// Returns the value of tokens owned by `account`.
+    async fn balance_of(&self, account: Address) -> Result<U256, Erc20Error>;
+
+    /// Returns the remaining number of tokens that `spender` will be
+    /// allowed to spend on behalf of `owner`.
+    async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Erc20Error>;
+}
+
+#[async_trait]
+impl<P, N> Erc20Read for LazyToken<P, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    async fn name(&self) -> Result<String, Erc20Error> {
+        Self::name(self).await
+    }
+
+    async fn symbol(&self) -> Result<String, Erc20Error> {
+        Self::symbol(self).await
+    }
+
+    async fn decimals(&self) -> Result<u8, Erc20Error> {
+        Self::decimals(self).await
+    }
+
+    async fn total_supply(&self) -> Result<U256, Erc20Error> {
+        Self::total_supply(self).await
+    }
+
+    async fn balance_of(&self, account: Address) -> Result<U256, Erc20Error> {
+        Self::balance_of(self, account).await
+    }
+
+    async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Erc20Error> {
+        Self::allowance(self, owner, spender).await
+    }
+}
+
+/// An [`Erc20Read`] adapter over a raw contract instance, e.g. the
+/// [`LazyToken::instance`] field, for code written against the trait.
+///
+/// Unlike [`LazyToken`], nothing is cached: every call, including `name`,
+/// `symbol` and `decimals`, queries the network. Tokens returning the name
+/// or symbol as a `bytes32` aren't supported either.
+#[derive(Debug)]
+pub struct InstanceReader<'a, P, N> {
+    instance: &'a Erc20ContractInstance<P, N>,
+}
+
+impl<'a, P, N> InstanceReader<'a, P, N> {
+    /// Creates a new [`InstanceReader`] reading through `instance`.
+    pub const fn new(instance: &'a Erc20ContractInstance<P, N>) -> Self {
+        Self { instance }
+    }
+}
+
+#[async_trait]
+impl<P, N> Erc20Read for InstanceReader<'_, P, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
+    async fn name(&self) -> Result<String, Erc20Error> {
+        Ok(self.instance.name().call().await?)
+    }
+
+    async fn symbol(&self) -> Result<String, Erc20Error> {
+        Ok(self.instance.symbol().call().await?)
+    }
+
+    async fn decimals(&self) -> Result<u8, Erc20Error> {
+        Ok(self.instance.decimals().call().await?)
+    }
+
+    async fn total_supply(&self) -> Result<U256, Erc20Error> {
+        Ok(self.instance.totalSupply().call().await?)
+    }
+
+    async fn balance_of(&self, account: Address) -> Result<U256, Erc20Error> {
+        Ok(self.instance.balanceOf(account).call().await?)
+    }
+
+    async fn allowance(&self, owner: Address, spender: Address) -> Result<U256, Erc20Error> {
+        Ok(self.instance.allowance(owner, spender).call().await?)
+    }
+}
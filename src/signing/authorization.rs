@@ -0,0 +1,209 @@
+use alloy::{
+    primitives::{FixedBytes, Signature},
+    signers::{Signer, SignerSync},
+};
+
+use crate::{Erc20Error, PermitParams};
+
+use super::{hash_permit, Eip712Domain};
+
+/// Signs an EIP-2612 permit with `signer`, which must be the permit owner.
+///
+/// Any alloy [`Signer`] can be used, e.g. a local, Ledger or AWS KMS signer.
+/// The deadline isn't checked: use [`sign_permit_checked`] to avoid signing
+/// an expired permit.
+pub async fn sign_permit<S>(
+    params: &PermitParams,
+    domain_separator: FixedBytes<32>,
+    signer: &S,
+) -> Result<Signature, alloy::signers::Error>
+where
+    S: Signer + ?Sized,
+{
+    signer
+        .sign_hash(&permit_digest(params, domain_separator))
+        .await
+}
+
+/// Signs an EIP-2612 permit with `signer` like [`sign_permit`], failing with
+/// [`Erc20Error::DeadlineExpired`] instead if the deadline has already
+/// passed according to [`PermitParams::validate`].
+///
+/// Not available on `wasm32` targets, which have no system clock: check the
+/// deadline with [`PermitParams::validate_at`] before [`sign_permit`] there.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn sign_permit_checked<S>(
+    params: &PermitParams,
+    domain_separator: FixedBytes<32>,
+    signer: &S,
+) -> Result<Signature, Erc20Error>
+where
+    S: Signer + ?Sized,
+{
+    params.validate()?;
+
+    Ok(sign_permit(params, domain_separator, signer).await?)
+}
+
+/// Signs an EIP-2612 permit with `signer`, which must be the permit owner.
+pub fn sign_permit_sync<S>(
+    params: &PermitParams,
+    domain_separator: FixedBytes<32>,
+    signer: &S,
+) -> Result<Signature, alloy::signers::Error>
+where
+    S: SignerSync + ?Sized,
+{
+    signer.sign_hash_sync(&permit_digest(params, domain_separator))
+}
+
+/// Signs an EIP-2612 permit under the given `domain` with `signer`, which
+/// must be the permit owner.
+///
+/// This is useful for tokens whose domain separator can't be built with
+/// [`build_domain_separator`](super::build_domain_separator), e.g. because
+/// it has a salt.
+pub async fn sign_permit_with_domain<S>(
+    params: &PermitParams,
+    domain: &Eip712Domain,
+    signer: &S,
+) -> Result<Signature, alloy::signers::Error>
+where
+    S: Signer + ?Sized,
+{
+    sign_permit(params, domain.domain_separator(), signer).await
+}
+
+fn permit_digest(params: &PermitParams, domain_separator: FixedBytes<32>) -> FixedBytes<32> {
+    hash_permit(
+        domain_separator,
+        params.owner,
+        params.spender,
+        params.value,
+        params.nonce,
+        params.deadline,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, FixedBytes, U256};
+    use alloy_signer_local::PrivateKeySigner;
+
+    use crate::{
+        signing::{hash_permit, Eip712Domain},
+        Erc20Error, PermitParams,
+    };
+    use alloy::signers::Signer;
+
+    use super::{sign_permit, sign_permit_checked, sign_permit_sync, sign_permit_with_domain};
+
+    #[tokio::test]
+    async fn test_sign_permit() {
+        let signer = PrivateKeySigner::random();
+        let domain_separator = FixedBytes::repeat_byte(0x42);
+        let params = PermitParams {
+            owner: signer.address(),
+            spender: Address::repeat_byte(1),
+            value: U256::from(1_000_000),
+            nonce: U256::ZERO,
+            deadline: U256::MAX,
+        };
+
+        let digest = hash_permit(
+            domain_separator,
+            params.owner,
+            params.spender,
+            params.value,
+            params.nonce,
+            params.deadline,
+        );
+
+        let signature = sign_permit(&params, domain_separator, &signer)
+            .await
+            .unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+
+        let signature = sign_permit_sync(&params, domain_separator, &signer).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+
+        // Through a trait object, as for remote signers
+        let dyn_signer: &dyn Signer = &signer;
+        let signature = sign_permit(&params, domain_separator, dyn_signer)
+            .await
+            .unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_permit_with_domain() {
+        let signer = PrivateKeySigner::random();
+        let domain = Eip712Domain::new("Token", "1", 1, Address::repeat_byte(2))
+            .with_salt(FixedBytes::repeat_byte(3));
+        let params = PermitParams {
+            owner: signer.address(),
+            spender: Address::repeat_byte(1),
+            value: U256::from(1_000_000),
+            nonce: U256::ZERO,
+            deadline: U256::MAX,
+        };
+
+        let digest = hash_permit(
+            domain.domain_separator(),
+            params.owner,
+            params.spender,
+            params.value,
+            params.nonce,
+            params.deadline,
+        );
+
+        let signature = sign_permit_with_domain(&params, &domain, &signer)
+            .await
+            .unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_permit_checked() {
+        let signer = PrivateKeySigner::random();
+        let domain_separator = FixedBytes::repeat_byte(0x42);
+        let params = PermitParams {
+            owner: signer.address(),
+            spender: Address::repeat_byte(1),
+            value: U256::from(1_000_000),
+            nonce: U256::ZERO,
+            deadline: U256::MAX,
+        };
+
+        let signature = sign_permit_checked(&params, domain_separator, &signer)
+            .await
+            .unwrap();
+        assert_eq!(
+            signature,
+            sign_permit(&params, domain_separator, &signer)
+                .await
+                .unwrap()
+        );
+
+        let expired = PermitParams {
+            deadline: U256::from(1),
+            ..params
+        };
+        assert!(matches!(
+            sign_permit_checked(&expired, domain_separator, &signer).await,
+            Err(Erc20Error::DeadlineExpired(_))
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use alloy::{
+    primitives::{b256, keccak256, Address, FixedBytes, B256, U256},
+    sol,
+    sol_types::{self, SolStruct},
+};
+
+sol! {
+    struct Permit {
+        address owner;
+        address spender;
+        uint256 value;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+/// The EIP-2612 permit type hash, i.e.
+/// `keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")`.
+pub const PERMIT_TYPEHASH: FixedBytes<32> =
+    b256!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9");
+
+/// The EIP-712 domain of a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip712Domain {
+    /// The token name, as used in the domain.
+    pub name: String,
+    /// The domain version, e.g. `"1"` or `"2"`.
+    pub version: String,
+    /// The chain id.
+    pub chain_id: u64,
+    /// The token contract address.
+    pub verifying_contract: Address,
+    /// The domain salt, used by some tokens to disambiguate their domain.
+    pub salt: Option<B256>,
+}
+
+impl Eip712Domain {
+    /// Creates a new [`Eip712Domain`] without salt.
+    pub fn new(name: &str, version: &str, chain_id: u64, verifying_contract: Address) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            chain_id,
+            verifying_contract,
+            salt: None,
+        }
+    }
+
+    /// Sets the domain salt.
+    pub const fn with_salt(mut self, salt: B256) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    /// Computes the domain separator, as returned by the token
+    /// `DOMAIN_SEPARATOR()`.
+    ///
+    /// This hashes the
+    /// `EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)`
+    /// struct, or the
+    /// `EIP712Domain(string name,string version,uint256 chainId,address verifyingContract,bytes32 salt)`
+    /// one if the domain has a salt.
+    pub fn domain_separator(&self) -> FixedBytes<32> {
+        sol_types::Eip712Domain::new(
+            Some(self.name.clone().into()),
+            Some(self.version.clone().into()),
+            Some(U256::from(self.chain_id)),
+            Some(self.verifying_contract),
+            self.salt,
+        )
+        .separator()
+    }
+}
+
+/// Builds the EIP-712 domain separator of a token from its `name`,
+/// `version`, `chain_id` and contract address, without querying its
+/// `DOMAIN_SEPARATOR()`.
+///
+/// See [`Eip712Domain`] for domains with a salt.
+pub fn build_domain_separator(
+    name: &str,
+    version: &str,
+    chain_id: u64,
+    verifying_contract: Address,
+) -> FixedBytes<32> {
+    Eip712Domain::new(name, version, chain_id, verifying_contract).domain_separator()
+}
+
+/// Computes the EIP-712 digest of a struct hash under the given domain
+/// separator, i.e. `keccak256("\x19\x01" ‖ domain_separator ‖ struct_hash)`.
+pub fn compute_eip712_digest(
+    domain_separator: FixedBytes<32>,
+    struct_hash: FixedBytes<32>,
+) -> FixedBytes<32> {
+    let mut message = [0u8; 66];
+    message[..2].copy_from_slice(&[0x19, 0x01]);
+    message[2..34].copy_from_slice(domain_separator.as_slice());
+    message[34..].copy_from_slice(struct_hash.as_slice());
+
+    keccak256(message)
+}
+
+/// Computes the EIP-712 digest of any `sol!` struct under the given domain
+/// separator, ready to be signed.
+pub fn hash_typed_data<S: SolStruct>(domain_separator: FixedBytes<32>, s: &S) -> FixedBytes<32> {
+    compute_eip712_digest(domain_separator, s.eip712_hash_struct())
+}
+
+/// Computes the EIP-712 digest of an EIP-2612 permit, ready to be signed by
+/// `owner`.
+pub fn hash_permit(
+    domain_separator: FixedBytes<32>,
+    owner: Address,
+    spender: Address,
+    value: U256,
+    nonce: U256,
+    deadline: U256,
+) -> FixedBytes<32> {
+    let permit = Permit {
+        owner,
+        spender,
+        value,
+        nonce,
+        deadline,
+    };
+
+    hash_typed_data(domain_separator, &permit)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{b256, keccak256, Address, B256, U256},
+        sol_types::{SolStruct, SolValue},
+    };
+
+    use crate::mainnet::USDC;
+
+    use super::{
+        build_domain_separator, hash_permit, hash_typed_data, Eip712Domain, Permit, PERMIT_TYPEHASH,
+    };
+
+    #[test]
+    fn test_build_domain_separator() {
+        // USDC's `DOMAIN_SEPARATOR()` on Ethereum mainnet
+        assert_eq!(
+            build_domain_separator("USD Coin", "2", 1, USDC.address),
+            b256!("06c37168a7db5138defc7866392bb87a741f9b3d104deb5094588ce041cae335")
+        );
+    }
+
+    #[test]
+    fn test_salted_domain_separator() {
+        let contract = Address::repeat_byte(1);
+        let salt = B256::repeat_byte(2);
+        let domain = Eip712Domain::new("Token", "1", 137, contract).with_salt(salt);
+
+        let type_hash = keccak256(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract,bytes32 salt)",
+        );
+        let expected = keccak256(
+            (
+                type_hash,
+                keccak256("Token"),
+                keccak256("1"),
+                U256::from(137),
+                contract,
+                salt,
+            )
+                .abi_encode(),
+        );
+
+        assert_eq!(domain.domain_separator(), expected);
+        assert_ne!(
+            domain.domain_separator(),
+            build_domain_separator("Token", "1", 137, contract)
+        );
+    }
+
+    #[test]
+    fn test_permit_typehash() {
+        assert_eq!(
+            PERMIT_TYPEHASH,
+            keccak256(
+                "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+            )
+        );
+        assert_eq!(
+            PERMIT_TYPEHASH,
+            keccak256(Permit::eip712_encode_type().as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_hash_typed_data() {
+        let domain = Eip712Domain::new("Token", "1", 1, Address::repeat_byte(1));
+        let permit = Permit {
+            owner: Address::repeat_byte(2),
+            spender: Address::repeat_byte(3),
+            value: U256::from(1_000_000),
+            nonce: U256::ZERO,
+            deadline: U256::MAX,
+        };
+
+        let digest = hash_typed_data(domain.domain_separator(), &permit);
+
+        assert_eq!(
+            digest,
+            permit.eip712_signing_hash(&alloy::sol_types::eip712_domain! {
+                name: "Token",
+                version: "1",
+                chain_id: 1,
+                verifying_contract: Address::repeat_byte(1),
+            })
+        );
+        assert_eq!(
+            digest,
+            hash_permit(
+                domain.domain_separator(),
+                permit.owner,
+                permit.spender,
+                permit.value,
+                permit.nonce,
+                permit.deadline,
+            )
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use alloy::signers::local::{LocalSignerError, MnemonicBuilder, PrivateKeySigner};
+
+/// Derives a signer from an English BIP-39 mnemonic `phrase` at the given
+/// BIP-32 `derivation_path`, e.g. `"m/44'/60'/0'/0/0"` for the first account
+/// of most wallets.
+///
+/// The signer can be used with all the `sign_*` functions.
+pub fn signer_from_mnemonic(
+    phrase: &str,
+    derivation_path: &str,
+) -> Result<PrivateKeySigner, LocalSignerError> {
+    MnemonicBuilder::english()
+        .phrase(phrase)
+        .derivation_path(derivation_path)?
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use super::signer_from_mnemonic;
+
+    // The mnemonic of the default Anvil and Hardhat accounts
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_signer_from_mnemonic() {
+        assert_eq!(
+            signer_from_mnemonic(PHRASE, "m/44'/60'/0'/0/0")
+                .unwrap()
+                .address(),
+            address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+        assert_eq!(
+            signer_from_mnemonic(PHRASE, "m/44'/60'/0'/0/1")
+                .unwrap()
+                .address(),
+            address!("70997970C51812dc3A010C7d01b50e0d17dc79C8")
+        );
+
+        assert!(signer_from_mnemonic("not a mnemonic", "m/44'/60'/0'/0/0").is_err());
+        assert!(signer_from_mnemonic(PHRASE, "not a path").is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! EIP-712 hashing and signing helpers for gasless token approvals.
+
+pub use alloy::primitives::SignatureError;
+#[cfg(feature = "local-signer")]
+pub use alloy::signers::local::PrivateKeySigner;
+
+mod authorization;
+#[cfg(not(target_arch = "wasm32"))]
+pub use authorization::sign_permit_checked;
+pub use authorization::{sign_permit, sign_permit_sync, sign_permit_with_domain};
+
+mod eip712;
+pub use eip712::{
+    build_domain_separator, compute_eip712_digest, hash_permit, hash_typed_data, Eip712Domain,
+    PERMIT_TYPEHASH,
+};
+
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
+#[cfg(feature = "mnemonic")]
+pub use alloy::signers::local::LocalSignerError;
+#[cfg(feature = "mnemonic")]
+pub use mnemonic::signer_from_mnemonic;
+
+mod signature;
+pub use signature::{normalize_signature, signature_from_bytes, signature_from_hex};
+
+pub mod permit2;
@@ -0,0 +1,153 @@
+//! Signing helpers for Uniswap's Permit2 allowance transfers.
+
+use alloy::{
+    primitives::{address, Address, FixedBytes, Signature, U256},
+    signers::{Signer, SignerSync},
+    sol,
+    sol_types::Eip712Domain,
+};
+
+use super::hash_typed_data;
+
+/// The address of the canonical Permit2 contract, the same on every chain.
+pub const PERMIT2_ADDRESS: Address = address!("000000000022D473030F116dDEE9F6B43aC78BA3");
+
+sol! {
+    /// The allowance granted to a spender by a [`PermitSingle`].
+    #[derive(Debug, PartialEq, Eq)]
+    struct PermitDetails {
+        /// The token granted.
+        address token;
+        /// The allowance amount.
+        uint160 amount;
+        /// The timestamp at which the allowance expires.
+        uint48 expiration;
+        /// The owner's Permit2 nonce for the token and spender.
+        uint48 nonce;
+    }
+
+    /// A Permit2 allowance for a single token.
+    #[derive(Debug, PartialEq, Eq)]
+    struct PermitSingle {
+        /// The allowance details.
+        PermitDetails details;
+        /// The spender receiving the allowance.
+        address spender;
+        /// The timestamp after which the signature is no longer valid.
+        uint256 sigDeadline;
+    }
+}
+
+/// Builds the domain separator of the canonical Permit2 contract on the
+/// chain with the given `chain_id`.
+///
+/// Unlike most tokens, the Permit2 domain has no version.
+pub fn permit2_domain_separator(chain_id: u64) -> FixedBytes<32> {
+    Eip712Domain::new(
+        Some("Permit2".into()),
+        None,
+        Some(U256::from(chain_id)),
+        Some(PERMIT2_ADDRESS),
+        None,
+    )
+    .separator()
+}
+
+/// Computes the EIP-712 digest of a Permit2 `PermitSingle`, ready to be
+/// signed by the token owner.
+pub fn hash_permit_single(
+    domain_separator: FixedBytes<32>,
+    permit: &PermitSingle,
+) -> FixedBytes<32> {
+    hash_typed_data(domain_separator, permit)
+}
+
+/// Signs a Permit2 `PermitSingle` with `signer`, which must be the token
+/// owner.
+pub async fn sign_permit2_single<S>(
+    permit: &PermitSingle,
+    domain_separator: FixedBytes<32>,
+    signer: &S,
+) -> Result<Signature, alloy::signers::Error>
+where
+    S: Signer + ?Sized,
+{
+    signer
+        .sign_hash(&hash_permit_single(domain_separator, permit))
+        .await
+}
+
+/// Signs a Permit2 `PermitSingle` with `signer`, which must be the token
+/// owner.
+pub fn sign_permit2_single_sync<S>(
+    permit: &PermitSingle,
+    domain_separator: FixedBytes<32>,
+    signer: &S,
+) -> Result<Signature, alloy::signers::Error>
+where
+    S: SignerSync + ?Sized,
+{
+    signer.sign_hash_sync(&hash_permit_single(domain_separator, permit))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{aliases::U48, b256, keccak256, Address, U160, U256},
+        sol_types::SolStruct,
+    };
+    use alloy_signer_local::PrivateKeySigner;
+
+    use crate::mainnet::USDC;
+
+    use super::{
+        hash_permit_single, permit2_domain_separator, sign_permit2_single,
+        sign_permit2_single_sync, PermitDetails, PermitSingle,
+    };
+
+    #[test]
+    fn test_permit2_typehashes() {
+        // `_PERMIT_DETAILS_TYPEHASH` and `_PERMIT_SINGLE_TYPEHASH` of
+        // Permit2's `PermitHash` library
+        assert_eq!(
+            keccak256(PermitDetails::eip712_encode_type().as_bytes()),
+            b256!("65626cad6cb96493bf6f5ebea28756c966f023ab9e8a83a7101849d5573b3678")
+        );
+        assert_eq!(
+            keccak256(PermitSingle::eip712_encode_type().as_bytes()),
+            b256!("f3841cd1ff0085026a6327b620b67997ce40f282c88a8e905a7a5626e310f3d0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_permit2_single() {
+        let signer = PrivateKeySigner::random();
+        let domain_separator = permit2_domain_separator(1);
+        let permit = PermitSingle {
+            details: PermitDetails {
+                token: USDC.address,
+                amount: U160::from(1_000_000),
+                expiration: U48::from(1_700_000_000),
+                nonce: U48::ZERO,
+            },
+            spender: Address::repeat_byte(1),
+            sigDeadline: U256::from(1_700_000_000),
+        };
+
+        let digest = hash_permit_single(domain_separator, &permit);
+
+        let signature = sign_permit2_single(&permit, domain_separator, &signer)
+            .await
+            .unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+
+        let signature = sign_permit2_single_sync(&permit, domain_separator, &signer).unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+    }
+}
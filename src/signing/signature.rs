@@ -0,0 +1,123 @@
+use alloy::{
+    hex,
+    primitives::{Signature, SignatureError},
+};
+
+/// Normalizes `signature` into its low-s form, flipping the parity, as
+/// required by implementations guarding against signature malleability
+/// (EIP-2).
+///
+/// Signatures already in low-s form are returned as is.
+pub fn normalize_signature(signature: Signature) -> Signature {
+    signature.normalized_s()
+}
+
+/// Parses a signature received as raw bytes, e.g. from a browser wallet.
+///
+/// Both the 65 bytes `r ‖ s ‖ v` representation, with `v` either `0`/`1` or
+/// `27`/`28`, and the 64 bytes EIP-2098 compact representation are
+/// accepted. The signature is normalized with [`normalize_signature`].
+pub fn signature_from_bytes(bytes: &[u8]) -> Result<Signature, SignatureError> {
+    let signature = match bytes {
+        [.., 0 | 1 | 27 | 28] if bytes.len() == 65 => Signature::from_raw(bytes),
+        [.., v] if bytes.len() == 65 => Err(SignatureError::InvalidParity(*v as u64)),
+        _ if bytes.len() == 64 => Ok(Signature::from_erc2098(bytes)),
+        _ => Err(SignatureError::FromBytes("expected 64 or 65 bytes")),
+    }?;
+
+    Ok(normalize_signature(signature))
+}
+
+/// Parses a signature received as a hex string, with or without the `0x`
+/// prefix.
+///
+/// See [`signature_from_bytes`] for the accepted representations.
+pub fn signature_from_hex(s: &str) -> Result<Signature, SignatureError> {
+    signature_from_bytes(&hex::decode(s)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        hex,
+        primitives::{uint, FixedBytes, Signature, SignatureError, U256},
+        signers::SignerSync,
+    };
+    use alloy_signer_local::PrivateKeySigner;
+
+    use super::{normalize_signature, signature_from_bytes, signature_from_hex};
+
+    /// The order of the secp256k1 curve.
+    const SECP256K1N: U256 =
+        uint!(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141_U256);
+
+    #[test]
+    fn test_normalize_signature() {
+        let signer = PrivateKeySigner::random();
+        let digest = FixedBytes::repeat_byte(0x42);
+        let signature = signer.sign_hash_sync(&digest).unwrap();
+        assert!(signature.s() <= SECP256K1N >> 1);
+
+        let high_s = Signature::new(signature.r(), SECP256K1N - signature.s(), !signature.v());
+        assert!(high_s.s() > SECP256K1N >> 1);
+        assert_eq!(
+            high_s.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+
+        let normalized = normalize_signature(high_s);
+        assert_eq!(normalized, signature);
+        assert_eq!(
+            normalized.recover_address_from_prehash(&digest).unwrap(),
+            signer.address()
+        );
+        assert_eq!(normalize_signature(signature), signature);
+
+        // Parsed signatures are normalized
+        assert_eq!(signature_from_bytes(&high_s.as_bytes()).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_signature_from_bytes() {
+        let signer = PrivateKeySigner::random();
+        let digest = FixedBytes::repeat_byte(0x42);
+        let signature = signer.sign_hash_sync(&digest).unwrap();
+
+        let mut bytes = signature.as_bytes();
+        assert_eq!(signature_from_bytes(&bytes).unwrap(), signature);
+
+        // `v` as 0/1 instead of 27/28
+        bytes[64] -= 27;
+        assert_eq!(signature_from_bytes(&bytes).unwrap(), signature);
+
+        assert_eq!(
+            signature_from_bytes(&signature.as_erc2098()).unwrap(),
+            signature
+        );
+        assert_eq!(
+            signature_from_hex(&hex::encode_prefixed(signature.as_bytes())).unwrap(),
+            signature
+        );
+        assert_eq!(
+            signature_from_bytes(&bytes)
+                .unwrap()
+                .recover_address_from_prehash(&digest)
+                .unwrap(),
+            signer.address()
+        );
+
+        bytes[64] = 37;
+        assert!(matches!(
+            signature_from_bytes(&bytes),
+            Err(SignatureError::InvalidParity(37))
+        ));
+        assert!(matches!(
+            signature_from_bytes(&bytes[..63]),
+            Err(SignatureError::FromBytes(_))
+        ));
+        assert!(matches!(
+            signature_from_hex("0xzz"),
+            Err(SignatureError::FromHex(_))
+        ));
+    }
+}
@@ -0,0 +1,411 @@
+use std::{cmp::Ordering, fmt, time::Duration};
+
+use alloy::primitives::{Address, AddressError, B256, U256};
+use futures::future::BoxFuture;
+
+use crate::{amount, Erc20Error};
+
+/// The metadata of an ERC-20 token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    /// The token name.
+    pub name: String,
+    /// The token symbol.
+    pub symbol: String,
+    /// The token decimals.
+    pub decimals: u8,
+}
+
+/// The metadata of a possibly non-standard ERC-20 token, whose fields are
+/// `None` when the token doesn't implement them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialMetadata {
+    /// The token name.
+    pub name: Option<String>,
+    /// The token symbol.
+    pub symbol: Option<String>,
+    /// The token decimals.
+    pub decimals: Option<u8>,
+}
+
+/// The parameters of an EIP-2612 permit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermitParams {
+    /// The token owner granting the allowance.
+    pub owner: Address,
+    /// The spender receiving the allowance.
+    pub spender: Address,
+    /// The allowance value.
+    pub value: U256,
+    /// The owner's current permit nonce, as returned by `nonces(owner)`.
+    pub nonce: U256,
+    /// The timestamp after which the permit is no longer valid.
+    pub deadline: U256,
+}
+
+impl PermitParams {
+    /// Sets the deadline to `duration` from now.
+    ///
+    /// This reads the system clock, so it isn't available on `wasm32`
+    /// targets: use [`PermitParams::with_deadline_at`] there.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_deadline_from_now(self, duration: Duration) -> Self {
+        self.with_deadline_at(unix_now(), duration)
+    }
+
+    /// Sets the deadline to `duration` after the Unix timestamp `now_secs`.
+    pub fn with_deadline_at(mut self, now_secs: u64, duration: Duration) -> Self {
+        self.deadline = U256::from(now_secs.saturating_add(duration.as_secs()));
+        self
+    }
+
+    /// Fails with [`Erc20Error::DeadlineExpired`] if the deadline is already
+    /// in the past, so no transaction is wasted on an expired permit.
+    ///
+    /// This is checked against the system clock, which may differ slightly
+    /// from the timestamp of the block including the permit, so it isn't
+    /// available on `wasm32` targets: use [`PermitParams::validate_at`]
+    /// there.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn validate(&self) -> Result<(), Erc20Error> {
+        self.validate_at(unix_now())
+    }
+
+    /// Fails with [`Erc20Error::DeadlineExpired`] if the deadline is before
+    /// the Unix timestamp `now_secs`.
+    pub fn validate_at(&self, now_secs: u64) -> Result<(), Erc20Error> {
+        if self.deadline < U256::from(now_secs) {
+            return Err(Erc20Error::DeadlineExpired(self.deadline));
+        }
+
+        Ok(())
+    }
+}
+
+/// An ERC-20 `Transfer` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferEvent {
+    /// The sender.
+    pub from: Address,
+    /// The recipient.
+    pub to: Address,
+    /// The transferred value.
+    pub value: U256,
+    /// The number of the block including the transfer.
+    pub block: u64,
+    /// The hash of the transaction emitting the event.
+    pub tx_hash: B256,
+}
+
+/// An ERC-20 `Approval` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApprovalEvent {
+    /// The token owner granting the allowance.
+    pub owner: Address,
+    /// The spender receiving the allowance.
+    pub spender: Address,
+    /// The new allowance value.
+    pub value: U256,
+    /// The number of the block including the approval.
+    pub block: u64,
+    /// The hash of the transaction emitting the event.
+    pub tx_hash: B256,
+}
+
+/// The state of an account in a token, read in a single request by
+/// [`LazyToken::account_snapshot`](crate::LazyToken::account_snapshot).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountSnapshot {
+    /// The token balance of the account.
+    pub balance: U256,
+    /// The allowance granted by the account to the spender.
+    pub allowance: U256,
+    /// The EIP-2612 permit nonce of the account, `None` if the token doesn't
+    /// implement `nonces`.
+    pub permit_nonce: Option<U256>,
+}
+
+/// An amount of a token, in base units along with the token decimals.
+///
+/// Amounts are displayed in human units, e.g. `1.500000` for 1500000 base
+/// units of a 6 decimals token. Amounts with different decimals belong to
+/// different tokens, so they can't be compared nor combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAmount {
+    /// The amount in base units.
+    pub raw: U256,
+    /// The token decimals.
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    /// Creates a new [`TokenAmount`].
+    pub const fn new(raw: U256, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Parses a decimal string like `"1,234.5"` into an amount of a token
+    /// with `decimals` decimals.
+    ///
+    /// See [`LazyToken::parse_amount`](crate::LazyToken::parse_amount) for
+    /// the accepted format.
+    pub fn from_decimal_str(s: &str, decimals: u8) -> Result<Self, Erc20Error> {
+        Ok(Self::new(amount::parse_units(s, decimals)?, decimals))
+    }
+
+    /// Formats the amount as a fixed-point string with `decimals`
+    /// fractional digits.
+    pub fn to_decimal_string(&self) -> String {
+        amount::format_units(self.raw, self.decimals)
+    }
+
+    /// Adds `other` to this amount, failing on overflow.
+    pub fn checked_add(self, other: Self) -> Result<Self, Erc20Error> {
+        self.check_decimals(&other)?;
+
+        self.raw
+            .checked_add(other.raw)
+            .map(|raw| Self::new(raw, self.decimals))
+            .ok_or_else(|| Erc20Error::AmountOverflow(format!("{self} + {other}")))
+    }
+
+    /// Subtracts `other` from this amount, failing if `other` is greater.
+    pub fn checked_sub(self, other: Self) -> Result<Self, Erc20Error> {
+        self.check_decimals(&other)?;
+
+        self.raw
+            .checked_sub(other.raw)
+            .map(|raw| Self::new(raw, self.decimals))
+            .ok_or_else(|| Erc20Error::AmountUnderflow(format!("{self} - {other}")))
+    }
+
+    const fn check_decimals(&self, other: &Self) -> Result<(), Erc20Error> {
+        if self.decimals == other.decimals {
+            Ok(())
+        } else {
+            Err(Erc20Error::DecimalsMismatch(self.decimals, other.decimals))
+        }
+    }
+}
+
+impl PartialOrd for TokenAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.decimals == other.decimals).then(|| self.raw.cmp(&other.raw))
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_decimal_string())
+    }
+}
+
+/// The options used to send and await the transactions sent by
+/// [`LazyToken`](crate::LazyToken).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxOptions {
+    /// The number of confirmations to await, 1 by default.
+    pub confirmations: u64,
+    /// The maximum time to await the confirmations, unbounded by default.
+    pub timeout: Option<Duration>,
+    /// The EIP-1559 maximum fee per gas, in wei. Estimated by the provider
+    /// by default.
+    pub max_fee_per_gas: Option<u128>,
+    /// The EIP-1559 maximum priority fee per gas, in wei. Estimated by the
+    /// provider by default.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// The gas limit. Estimated by the provider by default.
+    pub gas_limit: Option<u64>,
+}
+
+impl Default for TxOptions {
+    fn default() -> Self {
+        Self {
+            confirmations: 1,
+            timeout: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            gas_limit: None,
+        }
+    }
+}
+
+/// The policy used by [`LazyToken`](crate::LazyToken) to retry reads
+/// failing with a transport error, e.g. a rate limit or a timeout.
+///
+/// Reverts and decoding failures are never retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one. 1 by
+    /// default, i.e. no retry.
+    pub attempts: u32,
+    /// The delay before the first retry, doubled after each failed retry.
+    pub backoff: Duration,
+    /// The function waiting for the backoff delay before each retry.
+    ///
+    /// With the `tokio` feature, enabled by default, this is
+    /// `tokio::time::sleep`, which panics outside a Tokio runtime. Without
+    /// it, retries don't wait: set the timer of the runtime in use with
+    /// [`RetryPolicy::with_sleep`].
+    pub sleep: fn(Duration) -> BoxFuture<'static, ()>,
+}
+
+impl RetryPolicy {
+    /// Creates a [`RetryPolicy`] making at most `attempts` attempts, waiting
+    /// `backoff` before the first retry, with the default sleep function.
+    pub const fn new(attempts: u32, backoff: Duration) -> Self {
+        Self {
+            attempts,
+            backoff,
+            sleep: default_sleep,
+        }
+    }
+
+    /// Sets the function waiting for the backoff delay before each retry,
+    /// e.g. to use a runtime other than Tokio.
+    pub const fn with_sleep(mut self, sleep: fn(Duration) -> BoxFuture<'static, ()>) -> Self {
+        self.sleep = sleep;
+        self
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1, Duration::ZERO)
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn default_sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    Box::pin(tokio::time::sleep(duration))
+}
+
+#[cfg(not(feature = "tokio"))]
+fn default_sleep(_: Duration) -> BoxFuture<'static, ()> {
+    Box::pin(std::future::ready(()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// Parses an address, rejecting mixed-case inputs that fail the EIP-55
+/// checksum.
+///
+/// All lowercase or all uppercase inputs carry no checksum and are accepted
+/// as is.
+pub fn parse_checked_address(s: &str) -> Result<Address, AddressError> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    let is_mixed_case = digits.bytes().any(|b| b.is_ascii_lowercase())
+        && digits.bytes().any(|b| b.is_ascii_uppercase());
+
+    if is_mixed_case {
+        Address::parse_checksummed(s, None)
+    } else {
+        s.parse().map_err(AddressError::Hex)
+    }
+}
+
+/// Formats `address` as an EIP-55 checksummed string.
+pub fn to_checksum_string(address: &Address) -> String {
+    address.to_checksum(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::{address, Address, AddressError, U256};
+
+    use crate::Erc20Error;
+
+    use super::{parse_checked_address, to_checksum_string, PermitParams, TokenAmount};
+
+    #[test]
+    fn test_permit_deadline() {
+        let params = PermitParams {
+            owner: Address::repeat_byte(1),
+            spender: Address::repeat_byte(2),
+            value: U256::from(1_000_000),
+            nonce: U256::ZERO,
+            deadline: U256::from(1_700_000_000),
+        };
+
+        assert!(params.validate_at(1_700_000_000).is_ok());
+        assert!(matches!(
+            params.validate_at(1_700_000_001),
+            Err(Erc20Error::DeadlineExpired(deadline)) if deadline == U256::from(1_700_000_000)
+        ));
+
+        let params = params.with_deadline_at(1_700_000_000, Duration::from_secs(3600));
+        assert_eq!(params.deadline, U256::from(1_700_003_600));
+        assert!(params.validate_at(1_700_003_600).is_ok());
+        assert!(params.validate_at(1_700_003_601).is_err());
+    }
+
+    #[test]
+    fn test_token_amount() {
+        let one = TokenAmount::from_decimal_str("1", 6).unwrap();
+        let half = TokenAmount::new(U256::from(500_000), 6);
+
+        assert_eq!(one.checked_add(half).unwrap().to_string(), "1.500000");
+        assert_eq!(one.checked_sub(half).unwrap(), half);
+        assert!(half < one);
+
+        assert!(matches!(
+            half.checked_sub(one),
+            Err(Erc20Error::AmountUnderflow(_))
+        ));
+        assert!(matches!(
+            TokenAmount::new(U256::MAX, 6).checked_add(half),
+            Err(Erc20Error::AmountOverflow(_))
+        ));
+
+        let other = TokenAmount::new(U256::from(1), 18);
+        assert_eq!(one.partial_cmp(&other), None);
+        assert!(matches!(
+            one.checked_add(other),
+            Err(Erc20Error::DecimalsMismatch(6, 18))
+        ));
+    }
+
+    #[test]
+    fn test_parse_checked_address() {
+        let dai = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+
+        assert_eq!(
+            parse_checked_address("0x6B175474E89094C44Da98b954EedeAC495271d0F").unwrap(),
+            dai
+        );
+        assert_eq!(
+            parse_checked_address("0x6b175474e89094c44da98b954eedeac495271d0f").unwrap(),
+            dai
+        );
+        assert_eq!(
+            parse_checked_address("6B175474E89094C44DA98B954EEDEAC495271D0F").unwrap(),
+            dai
+        );
+
+        assert!(matches!(
+            parse_checked_address("0x6b175474E89094C44Da98b954EedeAC495271d0F"),
+            Err(AddressError::InvalidChecksum)
+        ));
+        assert!(matches!(
+            parse_checked_address("0x6b17"),
+            Err(AddressError::Hex(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_checksum_string() {
+        assert_eq!(
+            to_checksum_string(&address!("6b175474e89094c44da98b954eedeac495271d0f")),
+            "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+        );
+    }
+}
@@ -1,6 +1,222 @@
-use alloy::primitives::{address, U256};
-use alloy::providers::ProviderBuilder;
-use alloy_erc20_full::LazyToken;
+use alloy::consensus::{Transaction as _, TxEnvelope};
+use alloy::eips::{BlockId, BlockNumberOrTag, Decodable2718};
+use alloy::primitives::{address, keccak256, Address, Bloom, Bytes, B256, I256, U256};
+use alloy::providers::{
+    PendingTransactionBuilder, Provider, ProviderBuilder, WalletProvider, MULTICALL3_ADDRESS,
+};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{
+    simulate::{SimCallResult, SimulatedBlock},
+    Block, Log, Transaction,
+};
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolEvent};
+use alloy::transports::{mock::Asserter, TransportError, TransportFut};
+use alloy_erc20_full::signing::build_domain_separator;
+use alloy_erc20_full::{
+    balances_across_tokens, load_tokens, load_tokens_metadata, AccountSnapshot, ApprovalEvent,
+    Erc20Error, Erc20Read, InstanceReader, LazyToken, PartialMetadata, RetryPolicy, TokenMetadata,
+    TransferEvent, TxOptions,
+};
+use alloy_json_rpc::{RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest};
+use alloy_signer_local::PrivateKeySigner;
+use bigdecimal::BigDecimal;
+use futures::{future::BoxFuture, StreamExt};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::Service;
+
+sol! {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct MulticallResult {
+        bool success;
+        bytes returnData;
+    }
+
+    function aggregate3(Call3[] calldata calls) external payable returns (MulticallResult[] memory returnData);
+
+    function name() external view returns (string);
+
+    function symbol() external view returns (string);
+
+    function decimals() external view returns (uint8);
+
+    function totalSupply() external view returns (uint256);
+
+    function transfer(address to, uint256 value) external returns (bool);
+
+    function approve(address spender, uint256 value) external returns (bool);
+
+    function transferAndCall(address to, uint256 value, bytes data) external returns (bool);
+
+    function allowance(address owner, address spender) external view returns (uint256);
+
+    function nonces(address owner) external view returns (uint256);
+
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+}
+
+/// Encodes a successful Multicall3 `aggregate3` sub-call result
+fn success(return_data: Vec<u8>) -> MulticallResult {
+    MulticallResult {
+        success: true,
+        returnData: return_data.into(),
+    }
+}
+
+/// Responds to a JSON-RPC request given its method and params
+type Respond = dyn Fn(&str, &Value) -> Value + Send + Sync;
+
+/// A mock node answering each JSON-RPC request according to its method.
+///
+/// Unlike the [`Asserter`] queue, it doesn't depend on the order of the
+/// requests, which isn't deterministic when sending transactions: the
+/// fillers and the pending transaction heartbeat query the node
+/// concurrently.
+#[derive(Clone)]
+struct MockNode {
+    respond: Arc<Respond>,
+    requests: Arc<Mutex<Vec<(String, Value)>>>,
+}
+
+impl MockNode {
+    /// Answers the requests with `respond`, falling back to
+    /// [`node_response`] when it returns `None`.
+    fn new(respond: impl Fn(&str, &Value) -> Option<Value> + Send + Sync + 'static) -> Self {
+        Self {
+            respond: Arc::new(move |method, params| {
+                respond(method, params).unwrap_or_else(|| node_response(method, params))
+            }),
+            requests: Default::default(),
+        }
+    }
+
+    /// Returns the methods requested so far, in order
+    fn methods(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|(method, _)| method.clone()).collect()
+    }
+
+    /// Returns the transactions sent so far, in order
+    fn sent_transactions(&self) -> Vec<TxEnvelope> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .iter()
+            .filter(|(method, _)| method == "eth_sendRawTransaction")
+            .map(|(_, params)| {
+                let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                TxEnvelope::decode_2718(&mut raw.as_ref()).unwrap()
+            })
+            .collect()
+    }
+
+    /// Connects a provider signing with a random wallet to the node
+    fn provider(&self) -> impl Provider + WalletProvider + Clone {
+        ProviderBuilder::new()
+            .wallet(PrivateKeySigner::random())
+            .connect_client(RpcClient::new(self.clone(), true))
+    }
+
+    fn call(&self, request: SerializedRequest) -> Response {
+        let params = request.params().map_or(Value::Null, |params| {
+            serde_json::from_str(params.get()).unwrap()
+        });
+        let result = (self.respond)(request.method(), &params);
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request.method().to_string(), params));
+
+        Response {
+            id: request.id().clone(),
+            payload: ResponsePayload::Success(serde_json::value::to_raw_value(&result).unwrap()),
+        }
+    }
+}
+
+impl Service<RequestPacket> for MockNode {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let response = match request {
+            RequestPacket::Single(request) => ResponsePacket::Single(MockNode::call(self, request)),
+            RequestPacket::Batch(requests) => ResponsePacket::Batch(
+                requests
+                    .into_iter()
+                    .map(|request| MockNode::call(self, request))
+                    .collect(),
+            ),
+        };
+
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// The default [`MockNode`] responses: enough for filling, sending and
+/// confirming a transaction mined in block 1
+fn node_response(method: &str, params: &Value) -> Value {
+    match method {
+        "eth_chainId" => json!("0x1"),
+        "eth_blockNumber" => json!("0x1"),
+        "eth_getTransactionCount" => json!("0x0"),
+        "eth_estimateGas" => json!("0x186a0"),
+        "eth_gasPrice" | "eth_maxPriorityFeePerGas" => json!("0x1"),
+        "eth_feeHistory" => json!({
+            "oldestBlock": "0x1",
+            "baseFeePerGas": ["0x1", "0x1"],
+            "gasUsedRatio": [0.5],
+            "reward": [["0x1"]],
+        }),
+        "eth_sendRawTransaction" => {
+            let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+            json!(keccak256(&raw))
+        }
+        "eth_getTransactionReceipt" => receipt(&params[0]),
+        "eth_newBlockFilter" => json!("0x1"),
+        "eth_getFilterChanges" => json!([]),
+        _ => Value::Null,
+    }
+}
+
+/// A successful receipt of the transaction `tx_hash`, mined in block 1
+fn receipt(tx_hash: &Value) -> Value {
+    json!({
+        "transactionHash": tx_hash,
+        "transactionIndex": "0x0",
+        "blockHash": B256::repeat_byte(0xbb),
+        "blockNumber": "0x1",
+        "from": Address::ZERO,
+        "to": Address::ZERO,
+        "cumulativeGasUsed": "0x5208",
+        "gasUsed": "0x5208",
+        "effectiveGasPrice": "0x1",
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": Bloom::default(),
+        "type": "0x2",
+        "status": "0x1",
+    })
+}
 
 /// Test reading token metadata (name, symbol, decimals)
 /// Uses DAI on Ethereum mainnet as a known-good token
@@ -30,7 +246,7 @@ async fn test_lazy_token_metadata() {
 
     // Test decimals (cached)
     let decimals = dai.decimals().await.unwrap();
-    assert_eq!(*decimals, 18);
+    assert_eq!(decimals, 18);
 }
 
 /// Test reading balances
@@ -141,3 +357,1833 @@ fn test_lazy_token_address() {
 
     assert_eq!(dai.address(), &dai_address);
 }
+
+/// Test batched balances through Multicall3
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_lazy_token_balances_of() {
+    let rpc_url =
+        std::env::var("ETH_MAINNET_RPC").unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
+
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse().unwrap());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider).with_multicall_batch_size(2);
+
+    let vitalik = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+    let accounts = [vitalik, Address::ZERO, vitalik];
+
+    let balances = dai.balances_of(&accounts).await.unwrap();
+
+    // Order is preserved across batches
+    assert_eq!(balances.len(), 3);
+    assert_eq!(balances[0], balances[2]);
+    assert_eq!(balances[0], dai.balance_of(vitalik).await.unwrap());
+}
+
+/// Test that batched balances fall back to sequential calls without Multicall3
+#[tokio::test]
+async fn test_lazy_token_balances_of_without_multicall() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    // Multicall3 is not deployed: the call returns no data
+    asserter.push_success(&Bytes::new());
+    asserter.push_success(&Bytes::from(U256::from(1).to_be_bytes::<32>()));
+    asserter.push_success(&Bytes::from(U256::from(2).to_be_bytes::<32>()));
+
+    let balances = dai
+        .balances_of(&[Address::ZERO, Address::repeat_byte(1)])
+        .await
+        .unwrap();
+
+    assert_eq!(balances, vec![U256::from(1), U256::from(2)]);
+}
+
+/// Test reading the permit nonce and domain separator in one multicall
+#[tokio::test]
+async fn test_lazy_token_permit_preconditions() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token = LazyToken::new(Address::repeat_byte(1), provider);
+    let domain_separator = B256::repeat_byte(0x42);
+
+    let results = vec![
+        success(noncesCall::abi_encode_returns(&U256::from(3))),
+        success(DOMAIN_SEPARATORCall::abi_encode_returns(&domain_separator)),
+    ];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+
+    let preconditions = token
+        .permit_preconditions(Address::repeat_byte(2))
+        .await
+        .unwrap();
+    assert_eq!(preconditions, (U256::from(3), domain_separator));
+
+    // Once cached, only the nonce is read
+    asserter.push_success(&Bytes::from(U256::from(4).to_be_bytes::<32>()));
+
+    let preconditions = token
+        .permit_preconditions(Address::repeat_byte(2))
+        .await
+        .unwrap();
+    assert_eq!(preconditions, (U256::from(4), domain_separator));
+    assert!(asserter.read_q().is_empty());
+
+    // Without Multicall3, both are read one by one
+    let token = LazyToken::new(Address::repeat_byte(1), token.instance.provider().clone())
+        .with_multicall_address(None);
+    asserter.push_success(&Bytes::from(U256::from(5).to_be_bytes::<32>()));
+    asserter.push_success(&domain_separator);
+
+    let preconditions = token
+        .permit_preconditions(Address::repeat_byte(2))
+        .await
+        .unwrap();
+    assert_eq!(preconditions, (U256::from(5), domain_separator));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading an account balance, allowance and permit nonce at once
+#[tokio::test]
+async fn test_lazy_token_account_snapshot() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token = LazyToken::new(Address::repeat_byte(1), provider);
+    let (account, spender) = (Address::repeat_byte(2), Address::repeat_byte(3));
+
+    let results = vec![
+        success(U256::from(10).to_be_bytes::<32>().to_vec()),
+        success(allowanceCall::abi_encode_returns(&U256::from(5))),
+        success(noncesCall::abi_encode_returns(&U256::from(3))),
+    ];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+
+    assert_eq!(
+        token.account_snapshot(account, spender).await.unwrap(),
+        AccountSnapshot {
+            balance: U256::from(10),
+            allowance: U256::from(5),
+            permit_nonce: Some(U256::from(3)),
+        }
+    );
+
+    // Without Multicall3, they're read one by one, on a token without permits
+    let token = token.with_multicall_address(None);
+    asserter.push_success(&Bytes::from(U256::from(10).to_be_bytes::<32>()));
+    asserter.push_success(&Bytes::from(U256::from(5).to_be_bytes::<32>()));
+    asserter.push_failure_msg("execution reverted");
+
+    assert_eq!(
+        token.account_snapshot(account, spender).await.unwrap(),
+        AccountSnapshot {
+            balance: U256::from(10),
+            allowance: U256::from(5),
+            permit_nonce: None,
+        }
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that a domain separator set offline is never read from the chain
+#[tokio::test]
+async fn test_lazy_token_with_domain_separator() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let domain_separator = B256::repeat_byte(0x42);
+    let token =
+        LazyToken::new(Address::repeat_byte(1), provider).with_domain_separator(domain_separator);
+
+    assert_eq!(token.domain_separator().await.unwrap(), domain_separator);
+
+    asserter.push_success(&Bytes::from(U256::from(3).to_be_bytes::<32>()));
+
+    let preconditions = token
+        .permit_preconditions(Address::repeat_byte(2))
+        .await
+        .unwrap();
+    assert_eq!(preconditions, (U256::from(3), domain_separator));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test computing the domain separator and comparing it with the on-chain one
+#[tokio::test]
+async fn test_lazy_token_compute_domain_separator() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let usdc = LazyToken::new(usdc_address, provider);
+    let on_chain = build_domain_separator("USD Coin", "2", 1, usdc_address);
+
+    asserter.push_success(&Bytes::from(nameCall::abi_encode_returns(
+        &"USD Coin".to_string(),
+    )));
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&on_chain);
+
+    assert_eq!(
+        usdc.compute_domain_separator("2").await.unwrap(),
+        usdc.domain_separator().await.unwrap()
+    );
+
+    // The name is cached, the chain id is read again
+    asserter.push_success(&U256::from(1));
+    assert_ne!(usdc.compute_domain_separator("1").await.unwrap(), on_chain);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test checking the on-chain domain separator against the expected domain
+#[tokio::test]
+async fn test_lazy_token_assert_domain_matches() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let on_chain = build_domain_separator("USD Coin", "2", 1, usdc_address);
+    // The cached separator is bypassed
+    let usdc = LazyToken::new(usdc_address, provider).with_domain_separator(B256::ZERO);
+
+    // DOMAIN_SEPARATOR(), then eth_chainId
+    asserter.push_success(&on_chain);
+    asserter.push_success(&U256::from(1));
+
+    usdc.assert_domain_matches("USD Coin", "2").await.unwrap();
+
+    // A wrong version doesn't match
+    asserter.push_success(&on_chain);
+    asserter.push_success(&U256::from(1));
+
+    let err = usdc
+        .assert_domain_matches("USD Coin", "1")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Erc20Error::DomainMismatch(expected, actual)
+            if expected == build_domain_separator("USD Coin", "1", 1, usdc_address)
+                && actual == on_chain
+    ));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that metadata tolerates a reverting name()
+#[tokio::test]
+async fn test_lazy_token_metadata_with_reverting_name() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let results = vec![
+        MulticallResult {
+            success: false,
+            returnData: Bytes::new(),
+        },
+        success(symbolCall::abi_encode_returns(&"DAI".to_string())),
+        success(decimalsCall::abi_encode_returns(&18)),
+    ];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+    // name() is queried again on its own, and reverts
+    asserter.push_failure_msg("execution reverted");
+
+    let metadata = dai.metadata().await.unwrap();
+
+    assert_eq!(
+        metadata,
+        TokenMetadata {
+            name: String::new(),
+            symbol: "DAI".to_string(),
+            decimals: 18,
+        }
+    );
+
+    // symbol and decimals are now cached
+    assert_eq!(dai.symbol().await.unwrap(), "DAI");
+    assert_eq!(dai.decimals().await.unwrap(), 18);
+}
+
+/// Test that try_metadata leaves the missing fields empty
+#[tokio::test]
+async fn test_lazy_token_try_metadata() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token_address = Address::repeat_byte(1);
+    let token = LazyToken::new(token_address, provider);
+
+    let failure = || MulticallResult {
+        success: false,
+        returnData: Bytes::new(),
+    };
+    let results = vec![
+        failure(),
+        failure(),
+        success(decimalsCall::abi_encode_returns(&6)),
+    ];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+    // name() and symbol() are queried again on their own, and revert
+    asserter.push_failure_msg("execution reverted");
+    asserter.push_failure_msg("execution reverted");
+
+    let metadata = token.try_metadata().await.unwrap();
+
+    assert_eq!(
+        metadata,
+        PartialMetadata {
+            name: None,
+            symbol: None,
+            decimals: Some(6),
+        }
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading a bytes32 symbol (MKR)
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_lazy_token_bytes32_symbol() {
+    let rpc_url =
+        std::env::var("ETH_MAINNET_RPC").unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
+
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse().unwrap());
+
+    let mkr_address = address!("9f8F72aA9304c8B593d555F12eF6589cC3A579A2");
+    let mkr = LazyToken::new(mkr_address, provider);
+
+    assert_eq!(mkr.symbol().await.unwrap(), "MKR");
+    assert_eq!(mkr.name().await.unwrap(), "Maker");
+}
+
+/// Test that a bytes32 symbol is decoded and trimmed
+#[tokio::test]
+async fn test_lazy_token_bytes32_symbol_fallback() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let mkr_address = address!("9f8F72aA9304c8B593d555F12eF6589cC3A579A2");
+    let mkr = LazyToken::new(mkr_address, provider);
+
+    let symbol = Bytes::from(B256::right_padding_from(b"MKR"));
+    // The string decoding fails, then the bytes32 one succeeds
+    asserter.push_success(&symbol);
+    asserter.push_success(&symbol);
+
+    assert_eq!(mkr.symbol().await.unwrap(), "MKR");
+}
+
+/// Test reading total supply and balances at a historical block
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_lazy_token_reads_at_block() {
+    let rpc_url =
+        std::env::var("ETH_MAINNET_RPC").unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
+
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse().unwrap());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    // DAI deployment block: nothing was minted yet
+    let deployment = BlockId::number(8_928_158);
+    assert_eq!(dai.total_supply_at(deployment).await.unwrap(), U256::ZERO);
+
+    let vitalik = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+    assert_eq!(
+        dai.balance_of_at(vitalik, deployment).await.unwrap(),
+        U256::ZERO
+    );
+}
+
+/// Test that an invalidated symbol is queried again, on a shared token
+#[tokio::test]
+async fn test_lazy_token_invalidate_symbol() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let token = Arc::new(LazyToken::new(token_address, provider));
+
+    asserter.push_success(&Bytes::from(symbolCall::abi_encode_returns(
+        &"OLD".to_string(),
+    )));
+    assert_eq!(token.symbol().await.unwrap(), "OLD");
+
+    // Cached: no query
+    assert_eq!(token.symbol().await.unwrap(), "OLD");
+
+    token.invalidate_symbol();
+
+    asserter.push_success(&Bytes::from(symbolCall::abi_encode_returns(
+        &"NEW".to_string(),
+    )));
+    assert_eq!(token.symbol().await.unwrap(), "NEW");
+}
+
+/// Test streaming Transfer events
+#[tokio::test]
+async fn test_lazy_token_transfer_events() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let transfer = Transfer {
+        from: Address::repeat_byte(1),
+        to: Address::repeat_byte(2),
+        value: U256::from(42),
+    };
+    let log = Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: transfer.encode_log_data(),
+        },
+        block_number: Some(100),
+        transaction_hash: Some(B256::repeat_byte(3)),
+        ..Default::default()
+    };
+
+    // eth_newFilter, then eth_getFilterChanges
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&vec![log]);
+
+    let mut events = dai.transfer_events(BlockNumberOrTag::Latest).await.unwrap();
+
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        TransferEvent {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: U256::from(42),
+            block: 100,
+            tx_hash: B256::repeat_byte(3),
+        }
+    );
+}
+
+/// Test that Transfer events are queried by windows and concatenated
+#[tokio::test]
+async fn test_lazy_token_get_transfers() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let transfer_log = |block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Transfer {
+                from: Address::repeat_byte(1),
+                to: Address::repeat_byte(2),
+                value: U256::from(block),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        transaction_hash: Some(B256::repeat_byte(3)),
+        ..Default::default()
+    };
+
+    // Blocks 0 to 2500 take two eth_getLogs requests
+    asserter.push_success(&vec![transfer_log(10)]);
+    asserter.push_success(&vec![transfer_log(2100), transfer_log(2200)]);
+
+    let transfers = dai
+        .get_transfers(
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(2500),
+            Some(Address::repeat_byte(1)),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        transfers.iter().map(|t| t.block).collect::<Vec<_>>(),
+        vec![10, 2100, 2200]
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading the total supply changes from mints and burns
+#[tokio::test]
+async fn test_lazy_token_supply_deltas() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let transfer_log = |from: Address, to: Address, value: u64, block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Transfer {
+                from,
+                to,
+                value: U256::from(value),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        transaction_hash: Some(B256::repeat_byte(3)),
+        ..Default::default()
+    };
+    let account = Address::repeat_byte(1);
+
+    // Mints, then burns
+    asserter.push_success(&vec![
+        transfer_log(Address::ZERO, account, 100, 10),
+        transfer_log(Address::ZERO, account, 50, 30),
+    ]);
+    asserter.push_success(&vec![transfer_log(account, Address::ZERO, 20, 20)]);
+
+    let deltas = dai
+        .supply_deltas(BlockNumberOrTag::Number(0), BlockNumberOrTag::Number(100))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        deltas,
+        vec![
+            (10, I256::try_from(100).unwrap()),
+            (20, I256::try_from(-20).unwrap()),
+            (30, I256::try_from(50).unwrap()),
+        ]
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test resolving a token from its ENS name
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_lazy_token_from_ens() {
+    let rpc_url =
+        std::env::var("ETH_MAINNET_RPC").unwrap_or_else(|_| "https://eth.llamarpc.com".to_string());
+
+    let provider = ProviderBuilder::new().connect_http(rpc_url.parse().unwrap());
+
+    let dai = LazyToken::from_ens("dai.tokens.eth", provider.clone())
+        .await
+        .unwrap();
+    assert_eq!(dai.symbol().await.unwrap(), "DAI");
+
+    assert!(
+        LazyToken::from_ens("this-name-does-not-exist-erc20.eth", provider)
+            .await
+            .is_err()
+    );
+}
+
+/// Test that a transfer returning false is not sent
+#[tokio::test]
+async fn test_lazy_token_transfer_checked_returning_false() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let token_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let token = LazyToken::new(token_address, provider);
+
+    // balanceOf, then the simulated transfer
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+    asserter.push_success(&Bytes::from(transferCall::abi_encode_returns(&false)));
+
+    let err = token
+        .transfer_checked(Address::repeat_byte(1), U256::from(10))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::TransferReturnedFalse));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading allowances of many spenders through Multicall3
+#[tokio::test]
+async fn test_lazy_token_nonzero_allowances() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let allowance = |value: u64| success(U256::from(value).to_be_bytes::<32>().to_vec());
+    let results = vec![allowance(0), allowance(5), allowance(0)];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+
+    let spenders = [
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        Address::repeat_byte(3),
+    ];
+    let allowances = dai
+        .nonzero_allowances(Address::ZERO, &spenders)
+        .await
+        .unwrap();
+
+    assert_eq!(allowances, vec![(Address::repeat_byte(2), U256::from(5))]);
+}
+
+/// Test converting a balance to a decimal amount and back
+#[tokio::test]
+async fn test_lazy_token_to_base_units_round_trip() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let usdc = LazyToken::new(usdc_address, provider);
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&6)));
+
+    for raw in [
+        U256::ZERO,
+        U256::from(1),
+        U256::from(1_234_567_890),
+        U256::MAX,
+    ] {
+        let balance = usdc.get_balance(raw).await.unwrap();
+        assert_eq!(usdc.to_base_units(&balance).await.unwrap(), raw);
+    }
+}
+
+/// Test that approving the current allowance sends nothing
+#[tokio::test]
+async fn test_lazy_token_safe_approve_unchanged() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let usdt_address = address!("dAC17F958D2ee523a2206206994597C13D831ec7");
+    let usdt = LazyToken::new(usdt_address, provider);
+
+    asserter.push_success(&Bytes::from(allowanceCall::abi_encode_returns(
+        &U256::from(100),
+    )));
+
+    usdt.safe_approve(Address::repeat_byte(1), U256::from(100))
+        .await
+        .unwrap();
+
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that a non-zero allowance is reset to zero, and the reset mined,
+/// before approving the new amount
+#[tokio::test]
+async fn test_lazy_token_safe_approve_resets_first() {
+    let node = MockNode::new(|method, _| {
+        (method == "eth_call").then(|| {
+            json!(Bytes::from(allowanceCall::abi_encode_returns(&U256::from(
+                100
+            ))))
+        })
+    });
+
+    let usdt_address = address!("dAC17F958D2ee523a2206206994597C13D831ec7");
+    let usdt = LazyToken::new(usdt_address, node.provider());
+    let spender = Address::repeat_byte(1);
+
+    usdt.safe_approve(spender, U256::from(50)).await.unwrap();
+
+    let approvals: Vec<U256> = node
+        .sent_transactions()
+        .iter()
+        .map(|tx| {
+            assert_eq!(tx.to(), Some(usdt_address));
+            let call = approveCall::abi_decode(tx.input()).unwrap();
+            assert_eq!(call.spender, spender);
+            call.value
+        })
+        .collect();
+    assert_eq!(approvals, vec![U256::ZERO, U256::from(50)]);
+
+    // the reset's receipt is awaited before the approval is sent
+    let methods = node.methods();
+    let sent: Vec<usize> = methods
+        .iter()
+        .enumerate()
+        .filter(|(_, method)| *method == "eth_sendRawTransaction")
+        .map(|(i, _)| i)
+        .collect();
+    assert!(methods[sent[0]..sent[1]]
+        .iter()
+        .any(|method| method == "eth_getTransactionReceipt"));
+}
+
+/// Test that the gas overrides of the transaction options are sent as is,
+/// without estimating them
+#[tokio::test]
+async fn test_lazy_token_send_with_gas_overrides() {
+    let node = MockNode::new(|_, _| None);
+
+    let token =
+        LazyToken::new(Address::repeat_byte(1), node.provider()).with_tx_options(TxOptions {
+            max_fee_per_gas: Some(30_000_000_000),
+            max_priority_fee_per_gas: Some(2_000_000_000),
+            gas_limit: Some(60_000),
+            ..Default::default()
+        });
+
+    token.approve_max(Address::repeat_byte(2)).await.unwrap();
+
+    let sent = node.sent_transactions();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].max_fee_per_gas(), 30_000_000_000);
+    assert_eq!(sent[0].max_priority_fee_per_gas(), Some(2_000_000_000));
+    assert_eq!(sent[0].gas_limit(), 60_000);
+
+    let methods = node.methods();
+    assert!(!methods.iter().any(|method| method == "eth_estimateGas"));
+    assert!(!methods.iter().any(|method| method == "eth_feeHistory"));
+}
+
+/// Test that a transaction whose receipt doesn't arrive within the timeout
+/// fails with its hash
+#[tokio::test]
+async fn test_lazy_token_send_timed_out() {
+    let node =
+        MockNode::new(|method, _| (method == "eth_getTransactionReceipt").then_some(Value::Null));
+
+    let token =
+        LazyToken::new(Address::repeat_byte(1), node.provider()).with_tx_options(TxOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        });
+
+    let err = token
+        .approve_max(Address::repeat_byte(2))
+        .await
+        .unwrap_err();
+
+    let sent = node.sent_transactions();
+    assert_eq!(sent.len(), 1);
+    assert!(matches!(err, Erc20Error::TimedOut(tx_hash) if tx_hash == *sent[0].tx_hash()));
+}
+
+/// Test awaiting the confirmations of a transaction while reporting them
+#[tokio::test]
+async fn test_lazy_token_wait_with_progress() {
+    fn no_sleep(_: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(std::future::ready(()))
+    }
+
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let tx_hash = B256::repeat_byte(7);
+    let token = LazyToken::new(Address::repeat_byte(1), provider.clone())
+        .with_retry_policy(RetryPolicy::default().with_sleep(no_sleep));
+
+    // Not mined yet, then mined in block 1 at blocks 1 and 2
+    asserter.push_success(&Value::Null);
+    asserter.push_success(&receipt(&json!(tx_hash)));
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&receipt(&json!(tx_hash)));
+    asserter.push_success(&U256::from(2));
+
+    let mut updates = Vec::new();
+    let receipt = token
+        .wait_with_progress(
+            PendingTransactionBuilder::new(provider.root().clone(), tx_hash),
+            2,
+            |confirmations| updates.push(confirmations),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(receipt.transaction_hash, tx_hash);
+    assert_eq!(updates, vec![0, 1, 2]);
+    assert!(asserter.read_q().is_empty());
+
+    // Never mined within the timeout
+    let token = token.with_tx_options(TxOptions {
+        timeout: Some(Duration::ZERO),
+        ..Default::default()
+    });
+    asserter.push_success(&Value::Null);
+
+    let err = token
+        .wait_with_progress(
+            PendingTransactionBuilder::new(provider.root().clone(), tx_hash),
+            1,
+            |_| {},
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Erc20Error::TimedOut(hash) if hash == tx_hash));
+}
+
+/// Test detecting infinite allowances, including decremented ones
+#[tokio::test]
+async fn test_lazy_token_is_infinite_allowance() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    for (allowance, infinite) in [
+        (U256::MAX, true),
+        (U256::MAX - U256::from(1_000_000), true),
+        (U256::from(1_000_000), false),
+    ] {
+        asserter.push_success(&Bytes::from(allowanceCall::abi_encode_returns(&allowance)));
+
+        assert_eq!(
+            dai.is_infinite_allowance(Address::ZERO, Address::repeat_byte(1))
+                .await
+                .unwrap(),
+            infinite
+        );
+    }
+}
+
+/// Test telling missing methods and reverts apart from RPC errors
+#[tokio::test]
+async fn test_lazy_token_typed_errors() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let token = LazyToken::new(token_address, provider);
+
+    asserter.push_success(&Bytes::new());
+    assert!(matches!(
+        token.decimals().await,
+        Err(Erc20Error::MissingMethod(method)) if method == "decimals"
+    ));
+
+    asserter.push_failure_msg("execution reverted");
+    assert!(matches!(
+        token.decimals().await,
+        Err(Erc20Error::Reverted(data)) if data.is_empty()
+    ));
+
+    asserter.push_failure_msg("rate limited");
+    assert!(matches!(token.decimals().await, Err(Erc20Error::Rpc(_))));
+}
+
+/// Test caching the total supply for a given time
+#[tokio::test]
+async fn test_lazy_token_total_supply_cached() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    asserter.push_success(&Bytes::from(totalSupplyCall::abi_encode_returns(
+        &U256::from(1000),
+    )));
+    let ttl = Duration::from_secs(60);
+    assert_eq!(
+        dai.total_supply_cached(ttl).await.unwrap(),
+        U256::from(1000)
+    );
+
+    // Cached: no query
+    assert_eq!(
+        dai.total_supply_cached(ttl).await.unwrap(),
+        U256::from(1000)
+    );
+
+    asserter.push_success(&Bytes::from(totalSupplyCall::abi_encode_returns(
+        &U256::from(2000),
+    )));
+    assert_eq!(
+        dai.total_supply_cached(Duration::ZERO).await.unwrap(),
+        U256::from(2000)
+    );
+    assert_eq!(
+        dai.total_supply_cached(ttl).await.unwrap(),
+        U256::from(2000)
+    );
+}
+
+/// Test creating a token from environment variables
+#[test]
+fn test_lazy_token_from_env() {
+    std::env::set_var("FROM_ENV_TEST_RPC", "http://localhost:8545");
+    std::env::set_var(
+        "FROM_ENV_TEST_TOKEN",
+        "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+    );
+    std::env::set_var("FROM_ENV_TEST_BAD_TOKEN", "0x6B17");
+
+    let token = LazyToken::from_env("FROM_ENV_TEST_RPC", "FROM_ENV_TEST_TOKEN").unwrap();
+    assert_eq!(
+        *token.address(),
+        address!("6B175474E89094C44Da98b954EedeAC495271d0F")
+    );
+
+    assert!(matches!(
+        LazyToken::from_env("FROM_ENV_TEST_MISSING", "FROM_ENV_TEST_TOKEN"),
+        Err(Erc20Error::MissingEnvVar(var)) if var == "FROM_ENV_TEST_MISSING"
+    ));
+    assert!(matches!(
+        LazyToken::from_env("FROM_ENV_TEST_RPC", "FROM_ENV_TEST_BAD_TOKEN"),
+        Err(Erc20Error::InvalidEnvVar(var, _)) if var == "FROM_ENV_TEST_BAD_TOKEN"
+    ));
+}
+
+/// Test that a transfer returning nothing, like on USDT, is sent
+#[tokio::test]
+async fn test_lazy_token_transfer_checked_without_return_value() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let usdt_address = address!("dAC17F958D2ee523a2206206994597C13D831ec7");
+    let usdt = LazyToken::new(usdt_address, provider);
+
+    // balanceOf, then the simulated transfer returning no data
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+    asserter.push_success(&Bytes::new());
+    // Fail the first request made to send the transaction
+    asserter.push_failure_msg("unavailable");
+
+    let err = usdt
+        .transfer_checked(Address::repeat_byte(1), U256::from(10))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::Rpc(_)), "{err:?}");
+}
+
+/// Test loading the metadata of many tokens, in order
+#[tokio::test]
+async fn test_load_tokens_metadata() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let tokens = load_tokens(
+        &[
+            address!("6B175474E89094C44Da98b954EedeAC495271d0F"),
+            address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        ],
+        provider,
+    );
+
+    for (name, symbol, decimals) in [("Dai Stablecoin", "DAI", 18), ("USD Coin", "USDC", 6)] {
+        let results = vec![
+            success(nameCall::abi_encode_returns(&name.to_string())),
+            success(symbolCall::abi_encode_returns(&symbol.to_string())),
+            success(decimalsCall::abi_encode_returns(&decimals)),
+        ];
+        asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+    }
+
+    let metadata = load_tokens_metadata(&tokens, 1).await;
+
+    assert_eq!(metadata.len(), 2);
+    assert_eq!(metadata[0].as_ref().unwrap().symbol, "DAI");
+    assert_eq!(metadata[1].as_ref().unwrap().symbol, "USDC");
+    assert_eq!(tokens[1].decimals().await.unwrap(), 6);
+}
+
+/// Test reading the total supply and a balance scaled by the decimals
+#[tokio::test]
+async fn test_lazy_token_decimal_reads() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let usdc = LazyToken::new(usdc_address, provider);
+
+    asserter.push_success(&Bytes::from(totalSupplyCall::abi_encode_returns(
+        &U256::from(1_500_000),
+    )));
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&6)));
+    assert_eq!(
+        usdc.total_supply_decimal().await.unwrap(),
+        BigDecimal::from_str("1.5").unwrap()
+    );
+
+    // Decimals are cached
+    asserter.push_success(&Bytes::from(U256::from(250_000).to_be_bytes::<32>()));
+    assert_eq!(
+        usdc.balance_of_decimal(Address::ZERO).await.unwrap(),
+        BigDecimal::from_str("0.25").unwrap()
+    );
+    asserter.push_success(&Bytes::from(U256::from(250_000).to_be_bytes::<32>()));
+    assert_eq!(
+        usdc.balance_detailed(Address::ZERO).await.unwrap(),
+        (U256::from(250_000), BigDecimal::from_str("0.25").unwrap())
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that an overflowing allowance increase is not sent
+#[tokio::test]
+async fn test_lazy_token_increase_allowance_fallback_overflow() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    asserter.push_success(&Bytes::from(allowanceCall::abi_encode_returns(&U256::MAX)));
+
+    let err = dai
+        .increase_allowance_fallback(Address::repeat_byte(1), U256::from(1))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::AmountOverflow(_)));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test detecting a fee on transfer from a simulated transfer
+#[tokio::test]
+async fn test_lazy_token_detect_fee_on_transfer() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let token = LazyToken::new(token_address, provider);
+
+    let result = |return_data: Vec<u8>| SimCallResult {
+        return_data: return_data.into(),
+        logs: Vec::new(),
+        gas_used: 0,
+        status: true,
+        error: None,
+    };
+    let balance = |value: u64| result(U256::from(value).to_be_bytes::<32>().to_vec());
+
+    for (received, has_fee) in [(1000, false), (990, true)] {
+        let blocks = vec![SimulatedBlock {
+            inner: Block::<Transaction>::default(),
+            calls: vec![
+                balance(5),
+                result(transferCall::abi_encode_returns(&true)),
+                balance(5 + received),
+            ],
+        }];
+        asserter.push_success(&blocks);
+
+        assert_eq!(
+            token
+                .detect_fee_on_transfer_from(Address::repeat_byte(1), U256::from(1000))
+                .await
+                .unwrap(),
+            has_fee
+        );
+    }
+}
+
+/// Test sharing a single provider between tokens
+#[tokio::test]
+async fn test_lazy_token_new_shared() {
+    let asserter = Asserter::new();
+    let provider = Arc::new(ProviderBuilder::new().connect_mocked_client(asserter.clone()));
+
+    let dai = LazyToken::new_shared(
+        address!("6B175474E89094C44Da98b954EedeAC495271d0F"),
+        provider.clone(),
+    );
+    let usdc = LazyToken::new_shared(
+        address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        provider,
+    );
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&18)));
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&6)));
+
+    assert_eq!(dai.decimals().await.unwrap(), 18);
+    assert_eq!(usdc.decimals().await.unwrap(), 6);
+}
+
+/// Test reading an account balances across tokens, one of them reverting
+#[tokio::test]
+async fn test_balances_across_tokens() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let results = vec![
+        success(U256::from(1).to_be_bytes::<32>().to_vec()),
+        MulticallResult {
+            success: false,
+            returnData: Bytes::new(),
+        },
+        success(U256::from(3).to_be_bytes::<32>().to_vec()),
+    ];
+    asserter.push_success(&Bytes::from(aggregate3Call::abi_encode_returns(&results)));
+
+    let tokens = [
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        Address::repeat_byte(3),
+    ];
+    let balances =
+        balances_across_tokens(Address::ZERO, &tokens, &provider, Some(MULTICALL3_ADDRESS))
+            .await
+            .unwrap();
+
+    assert_eq!(
+        balances,
+        vec![Some(U256::from(1)), None, Some(U256::from(3))]
+    );
+
+    // Without Multicall3, the balances are read one by one
+    asserter.push_success(&Bytes::from(U256::from(1).to_be_bytes::<32>()));
+    asserter.push_failure_msg("execution reverted");
+    asserter.push_success(&Bytes::from(U256::from(3).to_be_bytes::<32>()));
+
+    let balances = balances_across_tokens(Address::ZERO, &tokens, &provider, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        balances,
+        vec![Some(U256::from(1)), None, Some(U256::from(3))]
+    );
+    assert!(asserter.read_q().is_empty());
+
+    // Other node errors fail the whole query
+    asserter.push_success(&Bytes::from(U256::from(1).to_be_bytes::<32>()));
+    asserter.push_failure_msg("too many requests");
+
+    let err = balances_across_tokens(Address::ZERO, &tokens, &provider, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Erc20Error::Rpc(_)));
+}
+
+/// Test reading the token price from a Chainlink feed
+#[cfg(feature = "chainlink")]
+#[tokio::test]
+async fn test_lazy_token_price_usd() {
+    use alloy::primitives::{aliases::U80, I256};
+
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    assert!(matches!(
+        dai.price_usd().await,
+        Err(Erc20Error::MissingPriceFeed)
+    ));
+
+    let dai = dai.with_price_feed(address!("Aed0c38402a5d19df6E4c03F4E2DceD6e29c1ee9"));
+
+    asserter.push_success(&Bytes::from(latestRoundDataCall::abi_encode_returns(
+        &latestRoundDataReturn {
+            roundId: U80::from(1),
+            answer: I256::try_from(99_980_000).unwrap(),
+            startedAt: U256::ZERO,
+            updatedAt: U256::ZERO,
+            answeredInRound: U80::from(1),
+        },
+    )));
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&8)));
+
+    assert_eq!(
+        dai.price_usd().await.unwrap(),
+        BigDecimal::from_str("0.9998").unwrap()
+    );
+}
+
+/// Test streaming Approval events and querying them by windows
+#[tokio::test]
+async fn test_lazy_token_approvals() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let approval_log = |block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Approval {
+                owner: Address::repeat_byte(1),
+                spender: Address::repeat_byte(2),
+                value: U256::from(block),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        transaction_hash: Some(B256::repeat_byte(3)),
+        ..Default::default()
+    };
+
+    // eth_newFilter, then eth_getFilterChanges
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&vec![approval_log(100)]);
+
+    let mut events = dai.approval_events(BlockNumberOrTag::Latest).await.unwrap();
+
+    assert_eq!(
+        events.next().await.unwrap().unwrap(),
+        ApprovalEvent {
+            owner: Address::repeat_byte(1),
+            spender: Address::repeat_byte(2),
+            value: U256::from(100),
+            block: 100,
+            tx_hash: B256::repeat_byte(3),
+        }
+    );
+
+    // Blocks 0 to 2500 take two eth_getLogs requests
+    asserter.push_success(&vec![approval_log(10)]);
+    asserter.push_success(&vec![approval_log(2100)]);
+
+    let approvals = dai
+        .get_approvals(
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(2500),
+            Some(Address::repeat_byte(1)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        approvals.iter().map(|a| a.block).collect::<Vec<_>>(),
+        vec![10, 2100]
+    );
+}
+
+/// Test dry-running a transfer and an approval without sending them
+#[tokio::test]
+async fn test_lazy_token_simulate() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let token_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let token = LazyToken::new(token_address, provider);
+
+    asserter.push_success(&Bytes::from(transferCall::abi_encode_returns(&true)));
+    assert!(token
+        .simulate_transfer(Address::repeat_byte(1), U256::from(10))
+        .await
+        .unwrap());
+
+    asserter.push_success(&Bytes::from(approveCall::abi_encode_returns(&false)));
+    assert!(!token
+        .simulate_approve(Address::repeat_byte(1), U256::from(10))
+        .await
+        .unwrap());
+
+    asserter.push_failure_msg("execution reverted");
+    let err = token
+        .simulate_transfer(Address::repeat_byte(1), U256::MAX)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::Reverted(_)));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// The backoff delays waited by `record_sleep`
+static SLEEPS: Mutex<Vec<Duration>> = Mutex::new(Vec::new());
+
+/// A retry sleep function recording its delays without waiting
+fn record_sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    SLEEPS.lock().unwrap().push(duration);
+    Box::pin(std::future::ready(()))
+}
+
+/// Test that reads are retried on transport errors, but not on reverts
+#[tokio::test]
+async fn test_lazy_token_retry_policy() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider)
+        .with_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)).with_sleep(record_sleep));
+
+    asserter.push_failure_msg("too many requests");
+    asserter.push_failure_msg("too many requests");
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+
+    let balance = dai.balance_of(Address::repeat_byte(1)).await.unwrap();
+
+    assert_eq!(balance, U256::from(1000));
+    assert!(asserter.read_q().is_empty());
+    // The backoff doubles after each retry
+    assert_eq!(
+        *SLEEPS.lock().unwrap(),
+        vec![Duration::from_millis(1), Duration::from_millis(2)]
+    );
+
+    // Out of attempts
+    for _ in 0..3 {
+        asserter.push_failure_msg("too many requests");
+    }
+    let err = dai.balance_of(Address::repeat_byte(1)).await.unwrap_err();
+
+    assert!(matches!(err, Erc20Error::Rpc(_)));
+    assert!(asserter.read_q().is_empty());
+
+    // Reverts are not retried
+    asserter.push_failure_msg("execution reverted");
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+    let err = dai.balance_of(Address::repeat_byte(1)).await.unwrap_err();
+
+    assert!(matches!(err, Erc20Error::Reverted(_)));
+    assert_eq!(asserter.read_q().len(), 1);
+}
+
+/// Test reading a token through the `Erc20Read` trait
+#[tokio::test]
+async fn test_lazy_token_erc20_read() {
+    async fn describe(token: &impl Erc20Read, account: Address) -> (String, u8, U256) {
+        (
+            token.symbol().await.unwrap(),
+            token.decimals().await.unwrap(),
+            token.balance_of(account).await.unwrap(),
+        )
+    }
+
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    asserter.push_success(&Bytes::from(symbolCall::abi_encode_returns(
+        &"DAI".to_string(),
+    )));
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&18)));
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+
+    assert_eq!(
+        describe(&dai, Address::repeat_byte(1)).await,
+        ("DAI".to_string(), 18, U256::from(1000))
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading a raw contract instance through `InstanceReader`, uncached
+#[tokio::test]
+async fn test_instance_reader() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    let reader = InstanceReader::new(&dai.instance);
+
+    for _ in 0..2 {
+        asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&18)));
+    }
+    asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+
+    // Each call queries the network
+    assert_eq!(reader.decimals().await.unwrap(), 18);
+    assert_eq!(reader.decimals().await.unwrap(), 18);
+    assert_eq!(
+        reader.balance_of(Address::repeat_byte(1)).await.unwrap(),
+        U256::from(1000)
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test overriding the decimals of a token
+#[tokio::test]
+async fn test_lazy_token_decimals_override() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = Arc::new(LazyToken::new(dai_address, provider));
+
+    let amount = U256::from(1_500_000);
+    assert_eq!(
+        dai.get_balance_with_decimals(amount, 6),
+        BigDecimal::from_str("1.5").unwrap()
+    );
+
+    // The override is set through a shared reference
+    dai.set_decimals_override(6);
+
+    assert_eq!(dai.decimals().await.unwrap(), 6);
+    assert_eq!(
+        dai.get_balance(amount).await.unwrap(),
+        BigDecimal::from_str("1.5").unwrap()
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test snapshotting the cached metadata without querying the network
+#[tokio::test]
+async fn test_lazy_token_cached_metadata() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    assert_eq!(dai.cached_metadata(), None);
+
+    asserter.push_success(&Bytes::from(nameCall::abi_encode_returns(
+        &"Dai Stablecoin".to_string(),
+    )));
+    asserter.push_success(&Bytes::from(symbolCall::abi_encode_returns(
+        &"DAI".to_string(),
+    )));
+    dai.name().await.unwrap();
+    dai.symbol().await.unwrap();
+
+    // The decimals are still missing
+    assert_eq!(dai.cached_metadata(), None);
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&18)));
+    dai.decimals().await.unwrap();
+
+    assert_eq!(
+        dai.cached_metadata(),
+        Some(TokenMetadata {
+            name: "Dai Stablecoin".to_string(),
+            symbol: "DAI".to_string(),
+            decimals: 18,
+        })
+    );
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that an ERC-677 transfer and call reverting or returning false is
+/// not sent
+#[tokio::test]
+async fn test_lazy_token_transfer_and_call_not_sent() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let link_address = address!("514910771AF9Ca656af840dff83E8264EcF986CA");
+    let link = LazyToken::new(link_address, provider);
+
+    asserter.push_success(&Bytes::from(transferAndCallCall::abi_encode_returns(
+        &false,
+    )));
+    let err = link
+        .transfer_and_call(Address::repeat_byte(1), U256::from(10), Bytes::new())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::TransferReturnedFalse));
+
+    asserter.push_failure_msg("execution reverted");
+    let err = link
+        .transfer_and_call(Address::repeat_byte(1), U256::from(10), Bytes::new())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Erc20Error::Reverted(_)));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that concurrent calls on a fresh token share a single request
+#[tokio::test(flavor = "multi_thread")]
+async fn test_lazy_token_concurrent_symbol() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = Arc::new(LazyToken::new(dai_address, provider));
+
+    // A second request would fail on the empty asserter
+    asserter.push_success(&Bytes::from(symbolCall::abi_encode_returns(
+        &"DAI".to_string(),
+    )));
+
+    let tasks = (0..50)
+        .map(|_| {
+            let dai = dai.clone();
+            tokio::spawn(async move { dai.symbol().await })
+        })
+        .collect::<Vec<_>>();
+
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap(), "DAI");
+    }
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that batch methods send the calls one by one without Multicall3
+#[tokio::test]
+async fn test_lazy_token_without_multicall() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider).with_multicall_address(None);
+
+    asserter.push_success(&Bytes::from(U256::from(1).to_be_bytes::<32>()));
+    asserter.push_success(&Bytes::from(U256::from(2).to_be_bytes::<32>()));
+
+    let balances = dai
+        .balances_of(&[Address::repeat_byte(1), Address::repeat_byte(2)])
+        .await
+        .unwrap();
+
+    assert_eq!(balances, vec![U256::from(1), U256::from(2)]);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test streaming the balance of an account as transfers touch it
+#[tokio::test]
+async fn test_lazy_token_watch_balance() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    let account = Address::repeat_byte(1);
+
+    let transfer_log = |from: Address, to: Address, block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Transfer {
+                from,
+                to,
+                value: U256::from(10),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        transaction_hash: Some(B256::repeat_byte(3)),
+        ..Default::default()
+    };
+
+    // eth_newFilter, then eth_getFilterChanges
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&vec![
+        transfer_log(Address::repeat_byte(2), Address::repeat_byte(3), 6),
+        transfer_log(Address::repeat_byte(2), account, 7),
+    ]);
+    // balanceOf at block 7
+    asserter.push_success(&Bytes::from(U256::from(110).to_be_bytes::<32>()));
+
+    let balances = dai.watch_balance(account).await.unwrap();
+    futures::pin_mut!(balances);
+
+    assert_eq!(balances.next().await.unwrap().unwrap(), U256::from(110));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that streaming Transfer events from a past block backfills them
+#[tokio::test]
+async fn test_lazy_token_transfer_events_from_past_block() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let transfer_log = |block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Transfer {
+                from: Address::repeat_byte(1),
+                to: Address::repeat_byte(2),
+                value: U256::from(block),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        ..Default::default()
+    };
+
+    // eth_newFilter, eth_blockNumber, then eth_getLogs up to block 100
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&U256::from(100));
+    asserter.push_success(&vec![transfer_log(90), transfer_log(100)]);
+    // eth_getFilterChanges, returning block 100 again
+    asserter.push_success(&vec![transfer_log(100), transfer_log(101)]);
+
+    let events = dai
+        .transfer_events(BlockNumberOrTag::Number(90))
+        .await
+        .unwrap();
+    let blocks = events
+        .take(3)
+        .map(|event| event.unwrap().block)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(blocks, vec![90, 100, 101]);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that watching approvals from a past block first queries the past
+/// events
+#[tokio::test]
+async fn test_lazy_token_approval_events_from_past_block() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    let approval_log = |block: u64| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Approval {
+                owner: Address::repeat_byte(1),
+                spender: Address::repeat_byte(2),
+                value: U256::from(block),
+            }
+            .encode_log_data(),
+        },
+        block_number: Some(block),
+        ..Default::default()
+    };
+
+    // eth_newFilter, eth_blockNumber, then eth_getLogs up to block 100
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&U256::from(100));
+    asserter.push_success(&vec![approval_log(90), approval_log(100)]);
+    // eth_getFilterChanges, returning block 100 again
+    asserter.push_success(&vec![approval_log(100), approval_log(101)]);
+
+    let events = dai
+        .approval_events(BlockNumberOrTag::Number(90))
+        .await
+        .unwrap();
+    let blocks = events
+        .take(3)
+        .map(|event| event.unwrap().block)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(blocks, vec![90, 100, 101]);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that watching a balance skips the transfers leaving it unchanged
+#[tokio::test]
+async fn test_lazy_token_watch_balance_skips_unchanged() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    let account = Address::repeat_byte(1);
+
+    let transfer_log = |from: Address, to: Address, value: u64, block: Option<u64>| Log {
+        inner: alloy::primitives::Log {
+            address: dai_address,
+            data: Transfer {
+                from,
+                to,
+                value: U256::from(value),
+            }
+            .encode_log_data(),
+        },
+        block_number: block,
+        ..Default::default()
+    };
+    let logs = vec![
+        transfer_log(Address::repeat_byte(2), account, 10, Some(100)),
+        // A self-transfer, and a pending zero-value transfer
+        transfer_log(account, account, 5, Some(101)),
+        transfer_log(Address::repeat_byte(2), account, 0, None),
+        // Not touching the account
+        transfer_log(
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            1,
+            Some(102),
+        ),
+        transfer_log(account, Address::repeat_byte(2), 3, Some(103)),
+    ];
+
+    // eth_newFilter, then eth_getFilterChanges and a balanceOf per matching log
+    asserter.push_success(&U256::from(1));
+    asserter.push_success(&logs);
+    for balance in [10u64, 10, 10, 7] {
+        asserter.push_success(&Bytes::from(U256::from(balance).to_be_bytes::<32>()));
+    }
+
+    let balances = dai.watch_balance(account).await.unwrap();
+    futures::pin_mut!(balances);
+
+    assert_eq!(balances.next().await.unwrap().unwrap(), U256::from(10));
+    assert_eq!(balances.next().await.unwrap().unwrap(), U256::from(7));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that implausible decimals are rejected, and not cached
+#[tokio::test]
+async fn test_lazy_token_implausible_decimals() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let token_address = Address::repeat_byte(1);
+    let token = LazyToken::new(token_address, provider.clone());
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&255)));
+    let err = token.decimals().await.unwrap_err();
+
+    assert!(matches!(err, Erc20Error::ImplausibleDecimals(255)));
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&18)));
+    assert_eq!(token.decimals().await.unwrap(), 18);
+
+    let token = LazyToken::new(token_address, provider).with_max_decimals(u8::MAX);
+
+    asserter.push_success(&Bytes::from(decimalsCall::abi_encode_returns(&255)));
+    assert_eq!(token.decimals().await.unwrap(), 255);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reading the implementation address of a proxy token
+#[tokio::test]
+async fn test_lazy_token_implementation_address() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let usdc = LazyToken::new(usdc_address, provider);
+
+    let implementation = address!("43506849D7C04F9138D1A2050bbF3A0c054402dd");
+    asserter.push_success(&implementation.into_word());
+    assert_eq!(
+        usdc.implementation_address().await.unwrap(),
+        Some(implementation)
+    );
+
+    // Not a proxy
+    asserter.push_success(&B256::ZERO);
+    assert_eq!(usdc.implementation_address().await.unwrap(), None);
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test that minting is not sent on tokens without a mint method
+#[tokio::test]
+async fn test_lazy_token_mint_unsupported() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .wallet(PrivateKeySigner::random())
+        .connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+
+    // eth_getCode, then the empty EIP-1967 implementation slot
+    asserter.push_success(&Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]));
+    asserter.push_success(&B256::ZERO);
+
+    let err = dai
+        .mint(Address::repeat_byte(1), U256::from(10))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        Erc20Error::UnsupportedOperation(method) if method == "mint(address,uint256)"
+    ));
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test finding a selector in the bytecode of a token or of its
+/// implementation
+#[tokio::test]
+async fn test_lazy_token_has_selector() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let usdc_address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    let usdc = LazyToken::new(usdc_address, provider);
+    let selector = approveCall::SELECTOR;
+    let code_with_selector = Bytes::from([&[0x63][..], &selector, &[0x14]].concat());
+
+    // Found in the token bytecode
+    asserter.push_success(&code_with_selector);
+    assert!(usdc.has_selector(selector).await.unwrap());
+
+    // Found in the bytecode of the EIP-1967 implementation
+    let implementation = Address::repeat_byte(9);
+    asserter.push_success(&Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]));
+    asserter.push_success(&implementation.into_word());
+    asserter.push_success(&code_with_selector);
+    assert!(usdc.has_selector(selector).await.unwrap());
+
+    // Found in neither
+    asserter.push_success(&Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]));
+    asserter.push_success(&implementation.into_word());
+    asserter.push_success(&Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]));
+    assert!(!usdc.has_selector(selector).await.unwrap());
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test computing the largest transferable amount with a gas reserve
+#[tokio::test]
+async fn test_lazy_token_max_transferable() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    let account = Address::repeat_byte(1);
+
+    for (reserve, expected) in [(0, 1000), (300, 700), (5000, 0)] {
+        asserter.push_success(&Bytes::from(U256::from(1000).to_be_bytes::<32>()));
+
+        assert_eq!(
+            dai.max_transferable(account, U256::from(reserve))
+                .await
+                .unwrap(),
+            U256::from(expected)
+        );
+    }
+    assert!(asserter.read_q().is_empty());
+}
+
+/// Test reusing a recently fetched allowance
+#[tokio::test]
+async fn test_lazy_token_allowance_cached() {
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new().connect_mocked_client(asserter.clone());
+
+    let dai_address = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let dai = LazyToken::new(dai_address, provider);
+    let (owner, spender) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let ttl = Duration::from_secs(60);
+
+    asserter.push_success(&Bytes::from(allowanceCall::abi_encode_returns(
+        &U256::from(100),
+    )));
+    assert_eq!(
+        dai.allowance_cached(owner, spender, ttl).await.unwrap(),
+        U256::from(100)
+    );
+
+    // Cached
+    assert_eq!(
+        dai.allowance_cached(owner, spender, ttl).await.unwrap(),
+        U256::from(100)
+    );
+    assert!(asserter.read_q().is_empty());
+
+    dai.invalidate_allowance(owner, spender);
+
+    asserter.push_success(&Bytes::from(allowanceCall::abi_encode_returns(
+        &U256::from(50),
+    )));
+    assert_eq!(
+        dai.allowance_cached(owner, spender, ttl).await.unwrap(),
+        U256::from(50)
+    );
+    assert!(asserter.read_q().is_empty());
+}